@@ -0,0 +1,298 @@
+//! Module exposing APIs based around framing `bytes` buffers into lines.
+use crate::delim::Delimiter;
+use crate::error::Error;
+use crate::limit::Overflow;
+use bytes::{Buf, Bytes, BytesMut};
+use std::io;
+
+/// Codec splitting `BytesMut` buffers into lines of `Bytes`.
+///
+/// This is used to frame input arriving in a buffer, which is split into
+/// owned lines without copying. Lines are split and stripped in the same
+/// way as `ByteLines`.
+#[derive(Clone, Debug)]
+pub struct ByteLinesCodec {
+    delimiter: Delimiter,
+    max_length: Option<(usize, Overflow)>,
+    next_index: usize,
+    discarding: bool,
+    truncated: Option<Bytes>,
+    line: u64,
+    offset: u64,
+    skipped: u64,
+}
+
+impl ByteLinesCodec {
+    /// Constructs a new `ByteLinesCodec` splitting on the provided delimiter.
+    pub fn new(delimiter: Delimiter) -> Self {
+        Self {
+            delimiter,
+            max_length: None,
+            next_index: 0,
+            discarding: false,
+            truncated: None,
+            line: 1,
+            offset: 0,
+            skipped: 0,
+        }
+    }
+
+    /// Sets the maximum length of a line, and how to handle lines exceeding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided maximum length is zero.
+    pub fn max_length(&mut self, max: usize, overflow: Overflow) {
+        assert!(max > 0, "maximum line length must be non-zero");
+        self.max_length = Some((max, overflow));
+    }
+
+    /// Decodes the next line from the buffer, if a full line is available.
+    pub fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Bytes>> {
+        loop {
+            // wait for more input before searching (again)
+            if self.next_index == src.len() {
+                return Ok(None);
+            }
+
+            let (buffered, chunk) = src.split_at(self.next_index);
+            let found = self.delimiter.find(buffered, chunk);
+
+            // no line yet, so check the length before waiting for more
+            let end = match found {
+                Some(n) => self.next_index + n,
+                None => {
+                    self.next_index = src.len();
+                    let (max, overflow) = match self.max_length {
+                        Some(limit) => limit,
+                        None => return Ok(None),
+                    };
+                    if src.len() <= max + self.delimiter.max_len() {
+                        return Ok(None);
+                    }
+
+                    // splitting just emits the start of the line
+                    if overflow == Overflow::Split && !self.discarding {
+                        self.next_index = 0;
+                        return Ok(Some(self.fragment(src, max)));
+                    }
+
+                    // keep the start of a truncated line until the line ends
+                    if overflow == Overflow::Truncate && !self.discarding {
+                        self.truncated = Some(src.split_to(max).freeze());
+                        self.skipped += max as u64;
+                    }
+
+                    // only keep enough to match a delimiter straddling reads
+                    let tail = self.delimiter.max_len() - 1;
+                    let skip = src.len() - tail;
+                    src.advance(skip);
+                    self.skipped += skip as u64;
+                    self.next_index = tail;
+
+                    // only report an error the first time
+                    let discarding = std::mem::replace(&mut self.discarding, true);
+                    if overflow == Overflow::Error && !discarding {
+                        return Err(self.overflow());
+                    }
+                    return Ok(None);
+                }
+            };
+
+            // reset for the next line
+            self.next_index = 0;
+
+            // finish discarding the line which was too long
+            if self.discarding {
+                self.discarding = false;
+                src.advance(end);
+                self.advance(end);
+                match self.truncated.take() {
+                    Some(line) => return Ok(Some(line)),
+                    None => continue,
+                }
+            }
+
+            return self.split(src, end).map(Some);
+        }
+    }
+
+    /// Decodes the next line from the buffer, treating the end of the buffer as EOF.
+    pub fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<Bytes>> {
+        if let Some(line) = self.decode(src)? {
+            return Ok(Some(line));
+        }
+
+        // EOF, so no more lines to come
+        self.next_index = 0;
+        if src.is_empty() || self.discarding {
+            self.discarding = false;
+            self.advance(src.len());
+            src.clear();
+            return Ok(self.truncated.take());
+        }
+
+        self.split(src, src.len()).map(Some)
+    }
+
+    /// Splits a line from the front of the buffer, stripping the delimiter.
+    fn split(&mut self, src: &mut BytesMut, end: usize) -> io::Result<Bytes> {
+        let (len, _) = self.delimiter.strip(&src[..end]);
+
+        // line was complete, but too long
+        let len = match self.max_length {
+            Some((max, overflow)) if len > max => match overflow {
+                Overflow::Error => {
+                    let err = self.overflow();
+                    src.advance(end);
+                    self.advance(end);
+                    return Err(err);
+                }
+                Overflow::Truncate => max,
+                Overflow::Split => return Ok(self.fragment(src, max)),
+            },
+            _ => len,
+        };
+
+        let mut line = src.split_to(end);
+        self.advance(end);
+        line.truncate(len);
+        Ok(line.freeze())
+    }
+
+    /// Splits a fragment of a line which is too long from the front of the buffer.
+    fn fragment(&mut self, src: &mut BytesMut, len: usize) -> Bytes {
+        self.offset += self.skipped + len as u64;
+        self.skipped = 0;
+        src.split_to(len).freeze()
+    }
+
+    /// Advances past the end of the current line, which ended after `len` more bytes.
+    fn advance(&mut self, len: usize) {
+        self.offset += self.skipped + len as u64;
+        self.skipped = 0;
+        self.line += 1;
+    }
+
+    /// Constructs an error for the current line exceeding the maximum length.
+    fn overflow(&self) -> io::Error {
+        Error::LineTooLong {
+            line: self.line,
+            offset: self.offset,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delim::LineEnding;
+    use bytes::BufMut;
+
+    #[test]
+    fn test_decode_lines() {
+        let mut codec = ByteLinesCodec::new(LineEnding::Any.into());
+        let mut buffer = BytesMut::new();
+        let mut lines = Vec::new();
+
+        for byte in b"one\rtwo\r\nthree\nfour" {
+            buffer.put_u8(*byte);
+            while let Some(line) = codec.decode(&mut buffer).unwrap() {
+                lines.push(line);
+            }
+        }
+        while let Some(line) = codec.decode_eof(&mut buffer).unwrap() {
+            lines.push(line);
+        }
+
+        assert_eq!(lines, vec![&b"one"[..], b"two", b"three", b"four"]);
+    }
+
+    #[test]
+    fn test_max_length() {
+        let mut codec = ByteLinesCodec::new(Delimiter::default());
+        codec.max_length(3, Overflow::Error);
+
+        let mut buffer = BytesMut::from(&b"one\nthree\n"[..]);
+
+        assert_eq!(codec.decode(&mut buffer).unwrap().unwrap(), &b"one"[..]);
+        assert!(codec.decode(&mut buffer).is_err());
+
+        let mut buffer = BytesMut::from(&b"three is long"[..]);
+        let err = codec.decode(&mut buffer).unwrap_err();
+        let inner = err.get_ref().unwrap().downcast_ref::<Error>();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(
+            inner,
+            Some(Error::LineTooLong {
+                line: 3,
+                offset: 10
+            })
+        ));
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+
+        buffer.extend_from_slice(b" indeed\ntwo\nthree\n");
+
+        assert_eq!(codec.decode(&mut buffer).unwrap().unwrap(), &b"two"[..]);
+
+        let err = codec.decode(&mut buffer).unwrap_err();
+        let inner = err.get_ref().unwrap().downcast_ref::<Error>();
+
+        assert!(matches!(
+            inner,
+            Some(Error::LineTooLong {
+                line: 5,
+                offset: 35
+            })
+        ));
+        assert!(codec.decode_eof(&mut buffer).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_max_length_overflow() {
+        let decode = |overflow, capacity: usize| {
+            let input = &b"one\nthree is long\ntwo\nfour\r\nlonger"[..];
+            let mut codec = ByteLinesCodec::new(Delimiter::default());
+            let mut buffer = BytesMut::new();
+            let mut lines = Vec::new();
+
+            codec.max_length(3, overflow);
+
+            for chunk in input.chunks(capacity) {
+                buffer.extend_from_slice(chunk);
+                while let Some(line) = codec.decode(&mut buffer).unwrap() {
+                    lines.push(line);
+                }
+            }
+            while let Some(line) = codec.decode_eof(&mut buffer).unwrap() {
+                lines.push(line);
+            }
+            lines
+        };
+
+        for capacity in 1..=8 {
+            assert_eq!(
+                decode(Overflow::Truncate, capacity),
+                vec![&b"one"[..], b"thr", b"two", b"fou", b"lon"]
+            );
+            assert_eq!(
+                decode(Overflow::Split, capacity),
+                vec![
+                    &b"one"[..],
+                    b"thr",
+                    b"ee ",
+                    b"is ",
+                    b"lon",
+                    b"g",
+                    b"two",
+                    b"fou",
+                    b"r",
+                    b"lon",
+                    b"ger"
+                ]
+            );
+        }
+    }
+}
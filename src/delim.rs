@@ -0,0 +1,416 @@
+//! Module exposing the delimiters used to split input into lines.
+
+/// Delimiters which can be used to split input into lines.
+///
+/// Delimiters can be constructed from a single byte or from a sequence
+/// of bytes, and are stripped from the end of each line yielded:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let mut lines = ByteLines::with_delimiter(&b"one\r\n\r\ntwo"[..], b"\r\n\r\n");
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), b"one");
+/// assert_eq!(lines.next().unwrap().unwrap(), b"two");
+/// assert!(lines.next().is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Split lines on a single byte.
+    ///
+    /// When splitting on `\n`, a leading `\r` is also stripped from each line.
+    Byte(u8),
+
+    /// Split lines on a sequence of bytes.
+    ///
+    /// Sequences are matched even when they straddle reads from the
+    /// underlying reader. An empty sequence never matches.
+    Sequence(Vec<u8>),
+
+    /// Split lines on newlines, using the provided line ending policy.
+    Line(LineEnding),
+}
+
+/// Policies for the line endings used when splitting on newlines.
+///
+/// The default policy splits on `\n` and strips an optional leading `\r`,
+/// which matches the behaviour of `BufRead::lines` in the standard library:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let input = &b"one\rtwo\r\nthree\n"[..];
+/// let mut lines = ByteLines::with_line_ending(input, LineEnding::Any);
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), b"one");
+/// assert_eq!(lines.next().unwrap().unwrap(), b"two");
+/// assert_eq!(lines.next().unwrap().unwrap(), b"three");
+/// assert!(lines.next().is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Split on `\n`, stripping only the `\n`.
+    Lf,
+
+    /// Split on `\r\n`, leaving any lone `\n` as part of the line.
+    Crlf,
+
+    /// Split on `\n`, stripping the `\n` and an optional leading `\r`.
+    LfOrCrlf,
+
+    /// Split on any of `\n`, `\r\n` or a lone `\r`.
+    Any,
+
+    /// Split on `\n`, leaving the terminator as part of the line.
+    Keep,
+}
+
+/// Default line ending implementation, splitting on LF or CRLF.
+impl Default for LineEnding {
+    #[inline]
+    fn default() -> Self {
+        LineEnding::LfOrCrlf
+    }
+}
+
+impl Delimiter {
+    /// Locates the end of a line within a chunk of input.
+    ///
+    /// The bytes of the current line which have already been buffered are
+    /// used to match sequences straddling two chunks. The returned value is
+    /// the number of bytes in the chunk up to and including the delimiter.
+    pub(crate) fn find(&self, buffered: &[u8], chunk: &[u8]) -> Option<usize> {
+        match self {
+            Delimiter::Byte(byte) => find_byte(*byte, chunk),
+            Delimiter::Sequence(seq) => find_sequence(seq, buffered, chunk),
+            Delimiter::Line(LineEnding::Crlf) => find_sequence(b"\r\n", buffered, chunk),
+            Delimiter::Line(LineEnding::Any) => {
+                // a trailing \r was buffered, so check for a following \n
+                if buffered.last() == Some(&b'\r') {
+                    return Some(if chunk.first() == Some(&b'\n') { 1 } else { 0 });
+                }
+
+                // find the first \r or \n, leaving a trailing \r for the next chunk
+                let i = memchr2(b'\n', b'\r', chunk)?;
+                match (chunk[i], chunk.get(i + 1)) {
+                    (b'\n', _) => Some(i + 1),
+                    (_, Some(b'\n')) => Some(i + 2),
+                    (_, Some(_)) => Some(i + 1),
+                    (_, None) => None,
+                }
+            }
+            Delimiter::Line(_) => find_byte(b'\n', chunk),
+        }
+    }
+
+    /// Returns the maximum length of a terminator matched by this delimiter.
+    pub(crate) fn max_len(&self) -> usize {
+        match self {
+            Delimiter::Byte(b'\n') => 2,
+            Delimiter::Byte(_) => 1,
+            Delimiter::Sequence(seq) => seq.len().max(1),
+            Delimiter::Line(LineEnding::Lf) => 1,
+            Delimiter::Line(_) => 2,
+        }
+    }
+
+    /// Returns the length of a line once stripped, along with the stripped terminator.
+    pub(crate) fn strip(&self, line: &[u8]) -> (usize, Terminator) {
+        let (len, terminator) = match self {
+            // LF or CRLF, with a lone CR being part of the line
+            Delimiter::Byte(b'\n') | Delimiter::Line(LineEnding::LfOrCrlf) => match newline(line) {
+                (_, Terminator::Cr) => (0, Terminator::None),
+                found => found,
+            },
+
+            // custom delimiters, which must be matched exactly
+            Delimiter::Byte(byte) if line.last() == Some(byte) => (1, Terminator::Delimiter),
+            Delimiter::Sequence(seq) if !seq.is_empty() && line.ends_with(seq) => {
+                (seq.len(), Terminator::Delimiter)
+            }
+
+            // specific line endings, which must be matched exactly
+            Delimiter::Line(LineEnding::Lf) if line.ends_with(b"\n") => (1, Terminator::Lf),
+            Delimiter::Line(LineEnding::Crlf) if line.ends_with(b"\r\n") => (2, Terminator::Crlf),
+
+            // any line ending, including a lone CR
+            Delimiter::Line(LineEnding::Any) => newline(line),
+
+            // LF or CRLF, but left in place on the line
+            Delimiter::Line(LineEnding::Keep) => match newline(line) {
+                (_, Terminator::Cr) => (0, Terminator::None),
+                (_, found) => (0, found),
+            },
+
+            // no delimiter, so must be EOF
+            _ => (0, Terminator::None),
+        };
+        (line.len() - len, terminator)
+    }
+}
+
+/// Terminators which can be found at the end of a line.
+///
+/// This is used to report how each line was terminated, allowing for
+/// format preserving rewrites of the input:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let mut lines = ByteLines::new(&b"one\r\ntwo"[..]);
+///
+/// assert_eq!(lines.next_with_ending().unwrap().unwrap(), (&b"one"[..], Terminator::Crlf));
+/// assert_eq!(lines.next_with_ending().unwrap().unwrap(), (&b"two"[..], Terminator::None));
+/// assert!(lines.next_with_ending().is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Terminator {
+    /// The line ended with `\n`.
+    Lf,
+
+    /// The line ended with `\r\n`.
+    Crlf,
+
+    /// The line ended with a lone `\r`.
+    Cr,
+
+    /// The line ended with a custom `Delimiter`.
+    Delimiter,
+
+    /// The line ended without a terminator, at EOF.
+    None,
+}
+
+/// Detects a newline terminator at the end of a line, along with its length.
+fn newline(line: &[u8]) -> (usize, Terminator) {
+    if line.ends_with(b"\r\n") {
+        (2, Terminator::Crlf)
+    } else if line.ends_with(b"\n") {
+        (1, Terminator::Lf)
+    } else if line.ends_with(b"\r") {
+        (1, Terminator::Cr)
+    } else {
+        (0, Terminator::None)
+    }
+}
+
+/// Locates the first instance of a byte within a chunk of input.
+fn find_byte(byte: u8, chunk: &[u8]) -> Option<usize> {
+    memchr(byte, chunk).map(|i| i + 1)
+}
+
+/// Locates the first instance of a sequence within a chunk of input.
+fn find_sequence(seq: &[u8], buffered: &[u8], chunk: &[u8]) -> Option<usize> {
+    if seq.is_empty() {
+        return None;
+    }
+
+    // check for a match starting in the buffered bytes
+    let carry = buffered.len().min(seq.len() - 1);
+    for k in (1..=carry).rev() {
+        let (head, tail) = seq.split_at(k);
+        if buffered.ends_with(head) && chunk.starts_with(tail) {
+            return Some(tail.len());
+        }
+    }
+
+    // otherwise check within the chunk itself, jumping between candidates
+    let mut start = 0;
+    while let Some(i) = memchr(seq[0], &chunk[start..]) {
+        let i = start + i;
+        if chunk[i..].starts_with(seq) {
+            return Some(i + seq.len());
+        }
+        start = i + 1;
+    }
+    None
+}
+
+/// Number of bytes checked at a time when searching for a byte.
+const WORD: usize = std::mem::size_of::<usize>();
+
+/// Word with the lowest bit of every byte set.
+const LO: usize = usize::MAX / 255;
+
+/// Word with the highest bit of every byte set.
+const HI: usize = LO << 7;
+
+/// Locates the first instance of a byte within a slice.
+///
+/// This checks a word at a time rather than a byte at a time, which is
+/// considerably faster on long lines, and only falls back to checking
+/// individual bytes once a word is known to contain a match.
+fn memchr(byte: u8, haystack: &[u8]) -> Option<usize> {
+    let needle = LO * byte as usize;
+    let mut words = haystack.chunks_exact(WORD);
+    for (i, word) in words.by_ref().enumerate() {
+        if contains_zero(read_word(word) ^ needle) {
+            return word.iter().position(|b| *b == byte).map(|j| i * WORD + j);
+        }
+    }
+    let tail = words.remainder();
+    let start = haystack.len() - tail.len();
+    tail.iter().position(|b| *b == byte).map(|j| start + j)
+}
+
+/// Locates the first instance of either of two bytes within a slice.
+fn memchr2(a: u8, b: u8, haystack: &[u8]) -> Option<usize> {
+    let (na, nb) = (LO * a as usize, LO * b as usize);
+    let mut words = haystack.chunks_exact(WORD);
+    for (i, word) in words.by_ref().enumerate() {
+        let value = read_word(word);
+        if contains_zero(value ^ na) || contains_zero(value ^ nb) {
+            return word
+                .iter()
+                .position(|c| *c == a || *c == b)
+                .map(|j| i * WORD + j);
+        }
+    }
+    let tail = words.remainder();
+    let start = haystack.len() - tail.len();
+    tail.iter()
+        .position(|c| *c == a || *c == b)
+        .map(|j| start + j)
+}
+
+/// Reads a word from a slice of exactly `WORD` bytes.
+#[inline(always)]
+fn read_word(bytes: &[u8]) -> usize {
+    let mut word = [0; WORD];
+    word.copy_from_slice(bytes);
+    usize::from_ne_bytes(word)
+}
+
+/// Determines whether any byte of a word is zero.
+#[inline(always)]
+fn contains_zero(word: usize) -> bool {
+    word.wrapping_sub(LO) & !word & HI != 0
+}
+
+/// Default delimiter implementation, splitting on the default line ending.
+impl Default for Delimiter {
+    #[inline]
+    fn default() -> Self {
+        Delimiter::Line(LineEnding::default())
+    }
+}
+
+/// Conversion from a line ending policy.
+impl From<LineEnding> for Delimiter {
+    #[inline]
+    fn from(ending: LineEnding) -> Self {
+        Delimiter::Line(ending)
+    }
+}
+
+/// Conversion from a single byte delimiter.
+impl From<u8> for Delimiter {
+    #[inline]
+    fn from(byte: u8) -> Self {
+        Delimiter::Byte(byte)
+    }
+}
+
+/// Conversion from a sequence of bytes.
+impl From<&[u8]> for Delimiter {
+    #[inline]
+    fn from(seq: &[u8]) -> Self {
+        Delimiter::Sequence(seq.to_vec())
+    }
+}
+
+/// Conversion from a fixed size sequence of bytes.
+impl<const N: usize> From<&[u8; N]> for Delimiter {
+    #[inline]
+    fn from(seq: &[u8; N]) -> Self {
+        Delimiter::Sequence(seq.to_vec())
+    }
+}
+
+/// Conversion from an owned sequence of bytes.
+impl From<Vec<u8>> for Delimiter {
+    #[inline]
+    fn from(seq: Vec<u8>) -> Self {
+        Delimiter::Sequence(seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_find() {
+        let delim = Delimiter::from(b"\n---\n");
+
+        assert_eq!(delim.find(b"", b"a\n---\nb"), Some(6));
+        assert_eq!(delim.find(b"a\n-", b"--\nb"), Some(3));
+        assert_eq!(delim.find(b"a\n--", b"-"), None);
+        assert_eq!(delim.find(b"a\n---", b"\nb\n---\n"), Some(1));
+        assert_eq!(delim.find(b"a\n-", b"x\n---\n"), Some(6));
+    }
+
+    #[test]
+    fn test_memchr() {
+        let mut haystack = vec![b'a'; 67];
+        assert_eq!(memchr(b'\n', &haystack), None);
+        assert_eq!(memchr2(b'\n', b'\r', &haystack), None);
+
+        for i in 0..haystack.len() {
+            haystack[i] = b'\n';
+            assert_eq!(memchr(b'\n', &haystack), Some(i));
+            assert_eq!(memchr(b'\n', &haystack[i + 1..]), None);
+            assert_eq!(memchr2(b'\r', b'\n', &haystack), Some(i));
+            haystack[i] = b'\x8a';
+            assert_eq!(memchr(b'\n', &haystack), None);
+            haystack[i] = b'a';
+        }
+    }
+
+    #[test]
+    fn test_any_find() {
+        let delim = Delimiter::from(LineEnding::Any);
+
+        assert_eq!(delim.find(b"", b"a\rb"), Some(2));
+        assert_eq!(delim.find(b"", b"a\r\nb"), Some(3));
+        assert_eq!(delim.find(b"", b"a\r"), None);
+        assert_eq!(delim.find(b"a\r", b"\nb"), Some(1));
+        assert_eq!(delim.find(b"a\r", b"b"), Some(0));
+        assert_eq!(delim.find(b"a\r", b""), Some(0));
+    }
+
+    #[test]
+    fn test_line_strip() {
+        let strip = |ending, line| Delimiter::from(ending).strip(line);
+
+        assert_eq!(strip(LineEnding::Lf, b"a\r\n"), (2, Terminator::Lf));
+        assert_eq!(strip(LineEnding::Crlf, b"a\r\n"), (1, Terminator::Crlf));
+        assert_eq!(strip(LineEnding::Crlf, b"a\n"), (2, Terminator::None));
+        assert_eq!(strip(LineEnding::LfOrCrlf, b"a\r\n"), (1, Terminator::Crlf));
+        assert_eq!(strip(LineEnding::LfOrCrlf, b"a\r"), (2, Terminator::None));
+        assert_eq!(strip(LineEnding::Any, b"a\r"), (1, Terminator::Cr));
+        assert_eq!(strip(LineEnding::Any, b"a\r\n"), (1, Terminator::Crlf));
+        assert_eq!(strip(LineEnding::Keep, b"a\r\n"), (3, Terminator::Crlf));
+        assert_eq!(strip(LineEnding::Keep, b"a"), (1, Terminator::None));
+    }
+
+    #[test]
+    fn test_strip() {
+        let strip = |delim: Delimiter, line| delim.strip(line);
+
+        assert_eq!(strip(Delimiter::default(), b"a\r\n"), (1, Terminator::Crlf));
+        assert_eq!(strip(Delimiter::from(b'\n'), b"a\n"), (1, Terminator::Lf));
+        assert_eq!(
+            strip(Delimiter::from(b'\0'), b"a\r\0"),
+            (2, Terminator::Delimiter)
+        );
+        assert_eq!(
+            strip(Delimiter::from(b"\r\n\r\n"), b"a\r\n\r\n"),
+            (1, Terminator::Delimiter)
+        );
+        assert_eq!(
+            strip(Delimiter::from(b"\r\n\r\n"), b"a\r\n"),
+            (3, Terminator::None)
+        );
+    }
+}
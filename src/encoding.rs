@@ -0,0 +1,375 @@
+//! Module exposing decoding of input in other text encodings into UTF-8.
+use std::char;
+use std::io::{BufRead, ErrorKind, Read, Result};
+
+/// Size of the chunks read from the underlying reader.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Mapping of the bytes `0x80` to `0x9F` in Windows-1252.
+///
+/// Bytes which are undefined are mapped to the matching C1 control codes,
+/// which is consistent with the WHATWG encoding specification.
+const WINDOWS_1252: [u16; 32] = [
+    0x20AC, 0x0081, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0x008D, 0x017D, 0x008F, 0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x009D, 0x017E, 0x0178,
+];
+
+/// Text encodings which can be decoded by a `TextDecoder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, which is passed through without any validation.
+    Utf8,
+
+    /// UTF-16 in little endian byte order.
+    Utf16Le,
+
+    /// UTF-16 in big endian byte order.
+    Utf16Be,
+
+    /// ISO-8859-1, where every byte maps to the matching code point.
+    Latin1,
+
+    /// Windows-1252, the superset of Latin-1 used by legacy Windows tools.
+    Windows1252,
+}
+
+/// Provides decoding of input into UTF-8, to allow reading lines via `ByteLines`.
+///
+/// Splitting lines of UTF-16 input on bytes would split characters in half,
+/// so input is decoded into UTF-8 before being split. As this structure is
+/// a `BufRead`, lines are split using the usual delimiters and line endings:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// // "one\r\ntwo" in UTF-16LE, with a BOM
+/// let input = &b"\xff\xfeo\0n\0e\0\r\0\n\0t\0w\0o\0"[..];
+/// let mut lines = ByteLines::new(TextDecoder::new(input));
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), b"one");
+/// assert_eq!(lines.next().unwrap().unwrap(), b"two");
+/// assert!(lines.next().is_none());
+/// ```
+///
+/// The encoding is detected from a byte order mark at the start of the input
+/// (which is then removed), falling back to a provided encoding otherwise.
+/// Any invalid input is replaced with `U+FFFD`, except for UTF-8 input which
+/// is passed through as is.
+///
+/// Lines read through a `TextDecoder` are split from the decoded UTF-8, so
+/// the offsets reported by `Position` and `Error::InvalidUtf8` are offsets
+/// into the decoded output rather than into the original input.
+pub struct TextDecoder<R> {
+    reader: R,
+    encoding: Encoding,
+    sniffed: bool,
+    eof: bool,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    consumed: usize,
+}
+
+impl<R> TextDecoder<R>
+where
+    R: Read,
+{
+    /// Constructs a new `TextDecoder`, detecting the encoding from the input.
+    ///
+    /// Input without a byte order mark is treated as UTF-8.
+    pub fn new(reader: R) -> Self {
+        Self::with_encoding(reader, Encoding::Utf8)
+    }
+
+    /// Constructs a new `TextDecoder`, using an encoding when input has no byte order mark.
+    pub fn with_encoding(reader: R, fallback: Encoding) -> Self {
+        Self {
+            reader,
+            encoding: fallback,
+            sniffed: false,
+            eof: false,
+            input: Vec::new(),
+            output: Vec::new(),
+            consumed: 0,
+        }
+    }
+
+    /// Retrieves the encoding used to decode the input.
+    ///
+    /// This is the fallback encoding until the input has first been read.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Retrieves a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Unwraps this `TextDecoder`, returning the underlying reader.
+    ///
+    /// Any input which has been read but not yet consumed is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads a chunk of input from the underlying reader.
+    fn read_input(&mut self) -> Result<()> {
+        let start = self.input.len();
+        self.input.resize(start + CHUNK_SIZE, 0);
+
+        let read = loop {
+            match self.reader.read(&mut self.input[start..]) {
+                Ok(read) => break read,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.input.truncate(start);
+                    return Err(e);
+                }
+            }
+        };
+
+        self.input.truncate(start + read);
+        self.eof = read == 0;
+        Ok(())
+    }
+
+    /// Detects the encoding of the input from a byte order mark, removing it.
+    fn sniff(&mut self) -> Result<()> {
+        while self.input.len() < 3 && !self.eof {
+            self.read_input()?;
+        }
+
+        let (encoding, bom) = match self.input[..] {
+            [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+            [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+            [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+            _ => (self.encoding, 0),
+        };
+
+        self.input.drain(..bom);
+        self.encoding = encoding;
+        self.sniffed = true;
+        Ok(())
+    }
+
+    /// Decodes as much of the buffered input as possible into the output.
+    fn decode(&mut self) {
+        let used = match self.encoding {
+            Encoding::Utf8 => {
+                self.output.extend_from_slice(&self.input);
+                self.input.len()
+            }
+            Encoding::Latin1 => {
+                for &byte in &self.input {
+                    push(&mut self.output, char::from(byte));
+                }
+                self.input.len()
+            }
+            Encoding::Windows1252 => {
+                for &byte in &self.input {
+                    let c = match byte {
+                        0x80..=0x9F => WINDOWS_1252[byte as usize - 0x80],
+                        _ => byte as u16,
+                    };
+                    push(&mut self.output, char::from_u32(c as u32).unwrap());
+                }
+                self.input.len()
+            }
+            Encoding::Utf16Le => self.decode_utf16(u16::from_le_bytes),
+            Encoding::Utf16Be => self.decode_utf16(u16::from_be_bytes),
+        };
+        self.input.drain(..used);
+    }
+
+    /// Decodes buffered UTF-16 input, returning the number of bytes used.
+    fn decode_utf16(&mut self, unit: fn([u8; 2]) -> u16) -> usize {
+        let mut units = self.input.len() / 2;
+
+        // leave a trailing high surrogate to be paired with the next chunk
+        if !self.eof && units > 0 {
+            let last = unit([self.input[units * 2 - 2], self.input[units * 2 - 1]]);
+            if (0xD800..0xDC00).contains(&last) {
+                units -= 1;
+            }
+        }
+
+        let input = self.input[..units * 2]
+            .chunks_exact(2)
+            .map(|pair| unit([pair[0], pair[1]]));
+
+        for c in char::decode_utf16(input) {
+            push(&mut self.output, c.unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+
+        // an odd byte at the end of the input is invalid
+        if self.eof && self.input.len() % 2 == 1 {
+            push(&mut self.output, char::REPLACEMENT_CHARACTER);
+            return self.input.len();
+        }
+
+        units * 2
+    }
+}
+
+/// `Read` implementation for `TextDecoder`, providing the decoded input.
+impl<R> Read for TextDecoder<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+/// `BufRead` implementation for `TextDecoder`, providing the decoded input.
+impl<R> BufRead for TextDecoder<R>
+where
+    R: Read,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        while self.consumed == self.output.len() {
+            if self.eof && self.input.is_empty() {
+                break;
+            }
+
+            if !self.sniffed {
+                self.sniff()?;
+            } else if !self.eof {
+                self.read_input()?;
+            }
+
+            self.output.clear();
+            self.consumed = 0;
+            self.decode();
+        }
+        Ok(&self.output[self.consumed..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.consumed = (self.consumed + amt).min(self.output.len());
+    }
+}
+
+/// Pushes a character onto a buffer of UTF-8.
+fn push(output: &mut Vec<u8>, c: char) {
+    output.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteLines, LineEnding};
+
+    /// Reader which only reads a single byte at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (&mut self.0).take(1).read(buf)
+        }
+    }
+
+    fn collect(input: &[u8], fallback: Encoding) -> Vec<String> {
+        let mut all = Vec::new();
+
+        for trickle in [false, true] {
+            let decoder = if trickle {
+                TextDecoder::with_encoding(Box::new(Trickle(input)) as Box<dyn Read>, fallback)
+            } else {
+                TextDecoder::with_encoding(Box::new(input) as Box<dyn Read>, fallback)
+            };
+
+            let mut lines = ByteLines::with_line_ending(decoder, LineEnding::Any);
+            let mut found = Vec::new();
+
+            while let Some(line) = lines.next() {
+                found.push(String::from_utf8(line.unwrap().to_vec()).unwrap());
+            }
+
+            all.push(found);
+        }
+
+        all.dedup();
+        assert_eq!(all.len(), 1);
+        all.remove(0)
+    }
+
+    fn utf16(input: &str, unit: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        input.encode_utf16().flat_map(unit).collect()
+    }
+
+    #[test]
+    fn test_utf16_bom() {
+        let text = "\u{feff}caf\u{e9}\r\n\u{1f600} \u{10348}\r\n\nlast";
+        let lines = vec!["café", "😀 𐍈", "", "last"];
+
+        assert_eq!(
+            collect(&utf16(text, u16::to_le_bytes), Encoding::Utf8),
+            lines
+        );
+        assert_eq!(
+            collect(&utf16(text, u16::to_be_bytes), Encoding::Utf8),
+            lines
+        );
+    }
+
+    #[test]
+    fn test_utf16_fallback() {
+        let text = "one\rtwo\n";
+
+        assert_eq!(
+            collect(&utf16(text, u16::to_le_bytes), Encoding::Utf16Le),
+            vec!["one", "two"]
+        );
+        assert_eq!(
+            collect(&utf16(text, u16::to_be_bytes), Encoding::Utf16Be),
+            vec!["one", "two"]
+        );
+    }
+
+    #[test]
+    fn test_utf16_invalid() {
+        // unpaired surrogates and a trailing odd byte
+        let input = b"\xff\xfe\x00\xd8a\0\n\0\x00\xdc\n\0b";
+        let lines = collect(input, Encoding::Utf8);
+
+        assert_eq!(lines, vec!["\u{fffd}a", "\u{fffd}", "\u{fffd}"]);
+    }
+
+    #[test]
+    fn test_utf8() {
+        let input = b"\xef\xbb\xbfone\ntwo\xff\n";
+        let mut decoder = TextDecoder::with_encoding(&input[..], Encoding::Latin1);
+        let mut output = Vec::new();
+
+        decoder.read_to_end(&mut output).unwrap();
+
+        assert_eq!(decoder.encoding(), Encoding::Utf8);
+        assert_eq!(output, b"one\ntwo\xff\n");
+    }
+
+    #[test]
+    fn test_single_byte() {
+        let input = b"na\xefve\r\n\x80 \x9d \xff\n";
+
+        assert_eq!(
+            collect(input, Encoding::Latin1),
+            vec!["naïve", "\u{80} \u{9d} ÿ"]
+        );
+        assert_eq!(
+            collect(input, Encoding::Windows1252),
+            vec!["naïve", "€ \u{9d} ÿ"]
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(collect(b"", Encoding::Utf16Le).is_empty());
+        assert!(collect(b"\xff\xfe", Encoding::Utf8).is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+//! Module exposing the errors raised when reading lines.
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Errors raised when reading lines, along with where in the input they occurred.
+///
+/// Readers in this crate continue to return `io::Error` for compatibility, with
+/// an `Error` provided as the inner error to give context on failures detected
+/// by the reader itself:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let input = &b"short\nmuch too long\n"[..];
+/// let mut lines = ByteLines::new(input).max_length(5, Overflow::Error);
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), b"short");
+///
+/// let err = lines.next().unwrap().unwrap_err();
+/// let err = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+///
+/// assert!(matches!(err, Error::LineTooLong { line: 2, offset: 6 }));
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error was returned by the underlying reader.
+    ///
+    /// Readers in this crate return errors from the underlying reader as they
+    /// are by default, to retain details such as `raw_os_error`. Readers opt
+    /// into this variant via `positioned_errors`, and the original error is
+    /// available as the `source`.
+    Io {
+        /// The error returned by the reader.
+        source: io::Error,
+        /// The number of the line being read, starting from 1.
+        line: u64,
+        /// The byte offset of the start of the line being read.
+        offset: u64,
+    },
+
+    /// A line exceeded the configured maximum length.
+    LineTooLong {
+        /// The number of the line, starting from 1.
+        line: u64,
+        /// The byte offset of the start of the line.
+        offset: u64,
+    },
+
+    /// A line contained invalid UTF-8 when reading as text.
+    InvalidUtf8 {
+        /// The number of the line, starting from 1.
+        line: u64,
+        /// The byte offset of the first invalid byte.
+        offset: u64,
+    },
+
+    /// The input ended before the final line was terminated.
+    UnexpectedEof {
+        /// The number of the unterminated line, starting from 1.
+        line: u64,
+        /// The byte offset of the start of the unterminated line.
+        offset: u64,
+    },
+}
+
+impl Error {
+    /// Retrieves the number of the line the error occurred on, starting from 1.
+    pub fn line(&self) -> u64 {
+        match *self {
+            Error::Io { line, .. }
+            | Error::LineTooLong { line, .. }
+            | Error::InvalidUtf8 { line, .. }
+            | Error::UnexpectedEof { line, .. } => line,
+        }
+    }
+
+    /// Retrieves the byte offset in the input the error occurred at.
+    pub fn offset(&self) -> u64 {
+        match *self {
+            Error::Io { offset, .. }
+            | Error::LineTooLong { offset, .. }
+            | Error::InvalidUtf8 { offset, .. }
+            | Error::UnexpectedEof { offset, .. } => offset,
+        }
+    }
+
+    /// Retrieves the `io::ErrorKind` used when converting into an `io::Error`.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::Io { source, .. } => source.kind(),
+            Error::LineTooLong { .. } | Error::InvalidUtf8 { .. } => io::ErrorKind::InvalidData,
+            Error::UnexpectedEof { .. } => io::ErrorKind::UnexpectedEof,
+        }
+    }
+}
+
+/// `Display` implementation for `Error`, including the position of the error.
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { source, .. } => write!(f, "{}", source)?,
+            Error::LineTooLong { .. } => write!(f, "line exceeds maximum length")?,
+            Error::InvalidUtf8 { .. } => write!(f, "line contains invalid UTF-8")?,
+            Error::UnexpectedEof { .. } => write!(f, "line is missing a terminator")?,
+        }
+        write!(f, " (line {}, offset {})", self.line(), self.offset())
+    }
+}
+
+/// `Error` implementation for `Error`, exposing the underlying I/O error.
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Conversion from `Error` into `io::Error`, retaining the same kind.
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::new(err.kind(), err)
+    }
+}
@@ -0,0 +1,260 @@
+//! Module exposing APIs based around indexing lines for random access.
+use crate::position::Position;
+use crate::std::ByteLines;
+use std::io::{BufReader, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+
+/// Magic bytes at the start of a serialized index.
+const MAGIC: &[u8; 4] = b"BLIX";
+
+/// Version of the serialized index format.
+const VERSION: u32 = 1;
+
+/// Sparse index of line numbers to byte offsets within an input.
+///
+/// An index is built by scanning the input once, recording the offset of every
+/// `interval` lines. The index can then be used to seek to any line directly,
+/// reading at most `interval` lines to reach the line requested:
+///
+/// ```rust
+/// use bytelines::*;
+/// use std::fs::File;
+///
+/// // build our index from our file input
+/// let file = File::open("./res/numbers.txt").unwrap();
+/// let index = LineIndex::build(file, 4).unwrap();
+///
+/// // seek directly to the 6th line of our file input
+/// let file = File::open("./res/numbers.txt").unwrap();
+/// let mut lines = index.seek(file, 6).unwrap();
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), b"5");
+/// ```
+///
+/// Indexes can be serialized using `write_to`, allowing them to be stored
+/// alongside the input and loaded using `read_from` to avoid a rescan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    interval: u64,
+    length: u64,
+    lines: u64,
+    offsets: Vec<u64>,
+}
+
+impl LineIndex {
+    /// Builds a new `LineIndex` by scanning an input `Read + Seek`.
+    ///
+    /// The offset of every `interval` lines is recorded, so smaller intervals
+    /// will result in faster seeking at the cost of a larger index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided interval is zero.
+    pub fn build<R>(mut reader: R, interval: u64) -> Result<Self, Error>
+    where
+        R: Read + Seek,
+    {
+        assert!(interval > 0, "index interval must be non-zero");
+
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut lines = ByteLines::new(BufReader::new(reader));
+        let mut index = Self {
+            interval,
+            length: 0,
+            lines: 0,
+            offsets: Vec::new(),
+        };
+
+        while let Some(line) = lines.next_with_position() {
+            let (_, position) = line?;
+            if (position.line - 1) % interval == 0 {
+                index.offsets.push(position.offset);
+            }
+            index.length = position.offset + position.length;
+            index.lines = position.line;
+        }
+
+        Ok(index)
+    }
+
+    /// Reads a serialized `LineIndex` from an input `Read`.
+    pub fn read_from<R>(mut reader: R) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "invalid line index"));
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+
+        if u32::from_le_bytes(version) != VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "unsupported line index version",
+            ));
+        }
+
+        let interval = read_u64(&mut reader)?;
+        let length = read_u64(&mut reader)?;
+        let lines = read_u64(&mut reader)?;
+        let count = read_u64(&mut reader)?;
+
+        if interval == 0 || count != lines.div_ceil(interval) {
+            return Err(Error::new(ErrorKind::InvalidData, "invalid line index"));
+        }
+
+        let offsets = (0..count)
+            .map(|_| read_u64(&mut reader))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            interval,
+            length,
+            lines,
+            offsets,
+        })
+    }
+
+    /// Writes this `LineIndex` in serialized form to an output `Write`.
+    pub fn write_to<W>(&self, mut writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.interval.to_le_bytes())?;
+        writer.write_all(&self.length.to_le_bytes())?;
+        writer.write_all(&self.lines.to_le_bytes())?;
+        writer.write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+
+        for offset in &self.offsets {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+
+        writer.flush()
+    }
+
+    /// Retrieves the interval between lines recorded in this index.
+    #[inline]
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Retrieves the number of lines in the indexed input.
+    #[inline]
+    pub fn lines(&self) -> u64 {
+        self.lines
+    }
+
+    /// Seeks an input `Read + Seek` to the provided line number, starting from 1.
+    ///
+    /// The returned `ByteLines` will yield the requested line first, followed by
+    /// the rest of the input. Positions are reported from the start of the input.
+    ///
+    /// An error of kind `InvalidData` is returned if the length of the input does
+    /// not match the indexed input, as the index is then likely to be stale.
+    pub fn seek<R>(&self, mut reader: R, line: u64) -> Result<ByteLines<BufReader<R>>, Error>
+    where
+        R: Read + Seek,
+    {
+        if line == 0 || line > self.lines {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "line number out of range",
+            ));
+        }
+
+        if reader.seek(SeekFrom::End(0))? != self.length {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "line index does not match input",
+            ));
+        }
+
+        // jump to the closest line we know about
+        let checkpoint = (line - 1) / self.interval;
+        let start = Position {
+            line: checkpoint * self.interval + 1,
+            offset: self.offsets[checkpoint as usize],
+            length: 0,
+        };
+        reader.seek(SeekFrom::Start(start.offset))?;
+
+        // walk the remaining lines to the one requested
+        let mut lines = ByteLines::with_position(BufReader::new(reader), start);
+        for _ in 0..(line - 1) % self.interval {
+            if let Some(Err(e)) = lines.next() {
+                return Err(e);
+            }
+        }
+
+        Ok(lines)
+    }
+}
+
+/// Reads a little endian `u64` from an input `Read`.
+fn read_u64<R>(reader: &mut R) -> Result<u64, Error>
+where
+    R: Read,
+{
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_seek_lines() {
+        let file = File::open("./res/numbers.txt").unwrap();
+        let index = LineIndex::build(file, 3).unwrap();
+
+        assert_eq!(index.lines(), 10);
+        assert_eq!(index.offsets, vec![0, 6, 12, 18]);
+
+        for line in 1..=10 {
+            let file = File::open("./res/numbers.txt").unwrap();
+            let mut lines = index.seek(file, line).unwrap();
+            let expected = format!("{}", line - 1);
+            let (found, position) = lines.next_with_position().unwrap().unwrap();
+
+            assert_eq!(found, expected.as_bytes());
+            assert_eq!(position.line, line);
+            assert_eq!(position.offset, (line - 1) * 2);
+        }
+
+        let file = File::open("./res/numbers.txt").unwrap();
+        assert!(index.seek(file, 11).is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let input = Cursor::new(&b"one\r\ntwo\nthree\r\nfour"[..]);
+        let index = LineIndex::build(input, 2).unwrap();
+
+        let mut buffer = Vec::new();
+        index.write_to(&mut buffer).unwrap();
+
+        let loaded = LineIndex::read_from(&buffer[..]).unwrap();
+        assert_eq!(loaded, index);
+
+        let input = Cursor::new(&b"one\r\ntwo\nthree\r\nfour"[..]);
+        let mut lines = loaded.seek(input, 4).unwrap();
+        assert_eq!(lines.next().unwrap().unwrap(), b"four");
+
+        assert!(LineIndex::read_from(&buffer[1..]).is_err());
+
+        let input = Cursor::new(&b"one\r\ntwo\nthree\r\nfour\nfive"[..]);
+        let err = loaded.seek(input, 4).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,215 @@
+//! Module exposing a lending iterator API, along with its adapters.
+//!
+//! Unlike `Iterator`, items of a `LendingIterator` can borrow from the
+//! iterator itself, which allows lines of `ByteLines` to be walked via
+//! combinators without the allocation of a `Vec` per line.
+
+/// Iteration over items which borrow from the iterator itself.
+///
+/// This is implemented by `ByteLines` (and other readers in this crate) to
+/// offer a subset of the `Iterator` combinators without any allocations:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let input = &b"# header\n1\n\n2\n3\n"[..];
+/// let total = ByteLines::new(input)
+///     .skip(1)
+///     .filter(|line| !matches!(line, Ok(b"")))
+///     .try_fold(0, |total, line| {
+///         let line = std::str::from_utf8(line.unwrap()).unwrap();
+///         Ok::<_, std::num::ParseIntError>(total + line.parse::<u64>()?)
+///     })
+///     .unwrap();
+///
+/// assert_eq!(total, 6);
+/// ```
+pub trait LendingIterator {
+    /// The type of the items being iterated over.
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// Retrieves the next item in the iterator (if any).
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+
+    /// Creates an iterator which only yields items matching a predicate.
+    fn filter<P>(self, predicate: P) -> Filter<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item<'_>) -> bool,
+    {
+        Filter {
+            iter: self,
+            predicate,
+        }
+    }
+
+    /// Creates an `Iterator` which maps items until the mapping returns `None`.
+    ///
+    /// As mapped values are owned, this converts back to a standard `Iterator`.
+    fn map_while<T, F>(self, f: F) -> MapWhile<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>) -> Option<T>,
+    {
+        MapWhile { iter: self, f }
+    }
+
+    /// Creates an iterator which skips the first `n` items.
+    fn skip(self, n: usize) -> Skip<Self>
+    where
+        Self: Sized,
+    {
+        Skip { iter: self, n }
+    }
+
+    /// Creates an iterator which yields only the first `n` items.
+    fn take(self, n: usize) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take { iter: self, n }
+    }
+
+    /// Calls a closure on each item of the iterator.
+    fn for_each<F>(mut self, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>),
+    {
+        while let Some(item) = self.next() {
+            f(item);
+        }
+    }
+
+    /// Folds every item into an accumulator, short circuiting on error.
+    fn try_fold<A, E, F>(&mut self, init: A, mut f: F) -> Result<A, E>
+    where
+        Self: Sized,
+        F: FnMut(A, Self::Item<'_>) -> Result<A, E>,
+    {
+        let mut acc = init;
+        while let Some(item) = self.next() {
+            acc = f(acc, item)?;
+        }
+        Ok(acc)
+    }
+
+    /// Consumes the iterator, counting the number of items.
+    fn count(mut self) -> usize
+    where
+        Self: Sized,
+    {
+        let mut count = 0;
+        while self.next().is_some() {
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Lending iterator which only yields items matching a predicate.
+///
+/// This is created by `LendingIterator::filter`.
+pub struct Filter<I, P> {
+    iter: I,
+    predicate: P,
+}
+
+impl<I, P> LendingIterator for Filter<I, P>
+where
+    I: LendingIterator,
+    P: FnMut(&I::Item<'_>) -> bool,
+{
+    type Item<'a>
+        = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<I::Item<'_>> {
+        let iter: *mut I = &mut self.iter;
+        loop {
+            // SAFETY: each item is either returned or dropped before the next
+            // call, so the borrows never overlap; the borrow checker rejects
+            // this conditional return of a borrow inside a loop, however.
+            let item = unsafe { (*iter).next()? };
+            if (self.predicate)(&item) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+/// Iterator which maps items until the mapping returns `None`.
+///
+/// This is created by `LendingIterator::map_while`.
+pub struct MapWhile<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<I, T, F> Iterator for MapWhile<I, F>
+where
+    I: LendingIterator,
+    F: FnMut(I::Item<'_>) -> Option<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let item = self.iter.next()?;
+        (self.f)(item)
+    }
+}
+
+/// Lending iterator which skips the first `n` items.
+///
+/// This is created by `LendingIterator::skip`.
+pub struct Skip<I> {
+    iter: I,
+    n: usize,
+}
+
+impl<I> LendingIterator for Skip<I>
+where
+    I: LendingIterator,
+{
+    type Item<'a>
+        = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<I::Item<'_>> {
+        while self.n > 0 {
+            self.n -= 1;
+            self.iter.next()?;
+        }
+        self.iter.next()
+    }
+}
+
+/// Lending iterator which yields only the first `n` items.
+///
+/// This is created by `LendingIterator::take`.
+pub struct Take<I> {
+    iter: I,
+    n: usize,
+}
+
+impl<I> LendingIterator for Take<I>
+where
+    I: LendingIterator,
+{
+    type Item<'a>
+        = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<I::Item<'_>> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        self.iter.next()
+    }
+}
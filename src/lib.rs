@@ -16,17 +16,52 @@ use ::std::io::BufRead;
 use ::tokio::io::AsyncBufRead;
 
 // mods
+mod delim;
+mod error;
+mod index;
+pub mod lending;
+mod limit;
+mod position;
+mod reverse;
+mod slice;
+mod source;
 mod std;
+mod text;
 mod util;
 
+#[cfg(feature = "encoding")]
+mod encoding;
+
+#[cfg(feature = "mmap")]
+mod mmap;
+
+#[cfg(feature = "tokio")]
+mod codec;
+
 #[cfg(feature = "tokio")]
 mod tokio;
 
 // expose all public APIs to keep the v2.x interface the same
+pub use crate::delim::{Delimiter, LineEnding, Terminator};
+pub use crate::error::Error;
+pub use crate::index::LineIndex;
+pub use crate::lending::LendingIterator;
+pub use crate::limit::Overflow;
+pub use crate::position::Position;
+pub use crate::reverse::ReverseByteLines;
+pub use crate::slice::SliceLines;
+pub use crate::source::{AsyncLineSource, LineSource, NextLine};
 pub use crate::std::{ByteLines, ByteLinesIter, ByteLinesReader};
+pub use crate::text::StrLines;
+
+#[cfg(feature = "encoding")]
+pub use crate::encoding::{Encoding, TextDecoder};
+
+#[cfg(feature = "mmap")]
+pub use crate::mmap::MmapLines;
 
 #[cfg(feature = "tokio")]
-pub use crate::tokio::AsyncByteLines;
+pub use crate::tokio::{AsyncByteLines, AsyncByteLinesReader, ByteLinesStream};
 
 /// Creates a new line reader from a stdlib `BufRead`.
 #[inline]
@@ -37,6 +72,12 @@ where
     ByteLines::new(reader)
 }
 
+/// Creates a new line iterator from an in-memory byte slice.
+#[inline]
+pub fn from_slice(bytes: &[u8]) -> SliceLines<'_> {
+    SliceLines::new(bytes)
+}
+
 /// Creates a new line reader from a Tokio `AsyncBufRead`.
 #[cfg(feature = "tokio")]
 #[inline]
@@ -0,0 +1,33 @@
+//! Module exposing the policies used to bound the length of lines.
+
+/// Policies for handling lines which exceed a maximum length.
+///
+/// A maximum length bounds the memory used to buffer a single line, which
+/// guards against input which never yields a delimiter:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let input = &b"short\nmuch too long\nshort\n"[..];
+/// let mut lines = ByteLines::new(input).max_length(5, Overflow::Truncate);
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), b"short");
+/// assert_eq!(lines.next().unwrap().unwrap(), b"much ");
+/// assert_eq!(lines.next().unwrap().unwrap(), b"short");
+/// assert!(lines.next().is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Return an error of kind `InvalidData`, discarding the rest of the line.
+    ///
+    /// Reading can be continued after the error, starting from the next line.
+    Error,
+
+    /// Truncate the line to the maximum length, discarding the rest of the line.
+    Truncate,
+
+    /// Split the line into fragments no longer than the maximum length.
+    ///
+    /// All fragments other than the last are reported as `Terminator::None`.
+    Split,
+}
@@ -0,0 +1,234 @@
+//! Module exposing APIs based around memory mapped files.
+use crate::slice::SliceLines;
+use std::fs::File;
+use std::io::Result;
+use std::path::Path;
+
+/// Provides iteration over lines of a memory mapped file.
+///
+/// Rather than reading the file through a `BufRead`, the file is mapped
+/// into memory and each line references the mapping directly, avoiding
+/// any copies of the input. Lines are split and stripped in the same way
+/// as the default handling of `ByteLines`:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// // map our file input into memory
+/// let lines = unsafe { MmapLines::open("./res/numbers.txt").unwrap() };
+///
+/// // walk our lines using `for` syntax
+/// for line in &lines {
+///     // do something with the line, which is &[u8]
+/// }
+/// ```
+///
+/// On platforms without support for memory mapping, and for files which
+/// can't be mapped (such as pipes, devices and files in `/proc`), the file
+/// is instead read into memory in full.
+pub struct MmapLines {
+    map: Mmap,
+}
+
+impl MmapLines {
+    /// Constructs a new `MmapLines` by mapping the file at the provided path.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified (by this or any other process) while it is
+    /// mapped, as this would change the contents of the lines referenced.
+    pub unsafe fn open<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_file(&File::open(path)?)
+    }
+
+    /// Constructs a new `MmapLines` by mapping an open file.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified (by this or any other process) while it is
+    /// mapped, as this would change the contents of the lines referenced.
+    pub unsafe fn from_file(file: &File) -> Result<Self> {
+        Ok(Self {
+            map: Mmap::map(file)?,
+        })
+    }
+
+    /// Retrieves a reference to the mapped bytes of the file.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.map.as_bytes()
+    }
+
+    /// Returns an iterator over the lines of the mapped file.
+    #[inline]
+    pub fn lines(&self) -> SliceLines<'_> {
+        SliceLines::new(self.as_bytes())
+    }
+}
+
+/// `IntoIterator` conversion for `&MmapLines` to provide `Iterator` APIs.
+impl<'a> IntoIterator for &'a MmapLines {
+    type Item = &'a [u8];
+    type IntoIter = SliceLines<'a>;
+
+    /// Constructs a `SliceLines` over the mapped file.
+    #[inline]
+    fn into_iter(self) -> SliceLines<'a> {
+        self.lines()
+    }
+}
+
+/// Read-only memory mapping of a file, or its contents when it can't be mapped.
+#[cfg(unix)]
+enum Mmap {
+    Mapped { ptr: *mut libc::c_void, len: usize },
+    Read(Vec<u8>),
+}
+
+#[cfg(unix)]
+impl Mmap {
+    /// Maps the provided file into memory.
+    unsafe fn map(mut file: &File) -> Result<Self> {
+        use std::convert::TryFrom;
+        use std::io::{Error, ErrorKind, Read};
+        use std::os::unix::io::AsRawFd;
+
+        let metadata = file.metadata()?;
+
+        // non-regular and procfs files report no length, so read them instead
+        if !metadata.is_file() || metadata.len() == 0 {
+            let mut buffer = Vec::new();
+            file.read_to_end(&mut buffer)?;
+            return Ok(Mmap::Read(buffer));
+        }
+
+        let len = usize::try_from(metadata.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "file is too large to map"))?;
+
+        let ptr = libc::mmap(
+            std::ptr::null_mut(),
+            len,
+            libc::PROT_READ,
+            libc::MAP_PRIVATE,
+            file.as_raw_fd(),
+            0,
+        );
+
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(Mmap::Mapped { ptr, len })
+    }
+
+    /// Retrieves a reference to the mapped bytes.
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Mmap::Mapped { ptr, len } => unsafe {
+                std::slice::from_raw_parts(*ptr as *const u8, *len)
+            },
+            Mmap::Read(buffer) => buffer,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mmap {
+    /// Unmaps the file from memory.
+    fn drop(&mut self) {
+        if let Mmap::Mapped { ptr, len } = *self {
+            unsafe { libc::munmap(ptr, len) };
+        }
+    }
+}
+
+// mappings are read-only, so they can be shared freely
+#[cfg(unix)]
+unsafe impl Send for Mmap {}
+#[cfg(unix)]
+unsafe impl Sync for Mmap {}
+
+/// Fallback for platforms without memory mapping, reading the file in full.
+#[cfg(not(unix))]
+struct Mmap(Vec<u8>);
+
+#[cfg(not(unix))]
+impl Mmap {
+    /// Reads the provided file into memory.
+    unsafe fn map(mut file: &File) -> Result<Self> {
+        use std::io::Read;
+
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+        Ok(Self(buffer))
+    }
+
+    /// Retrieves a reference to the read bytes.
+    fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_range_loop)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_basic_iterator() {
+        let lines = unsafe { MmapLines::open("./res/numbers.txt").unwrap() };
+        let lines = lines.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 10);
+        for i in 0..10 {
+            assert_eq!(lines[i], format!("{}", i).as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_empty_line() {
+        let lines = unsafe { MmapLines::open("./res/empty.txt").unwrap() };
+        let lines = lines.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines, vec![b""]);
+    }
+
+    #[test]
+    fn test_edge_files() {
+        let temp = |name: &str, contents: &[u8]| {
+            let name = format!("bytelines-mmap-{}-{}.txt", name, std::process::id());
+            let path = env::temp_dir().join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        };
+
+        let path = temp("empty", b"");
+        let lines = unsafe { MmapLines::open(&path).unwrap() };
+        assert_eq!(lines.lines().count(), 0);
+
+        drop(lines);
+        fs::remove_file(&path).unwrap();
+
+        let path = temp("unterminated", b"one\r\ntwo");
+        let lines = unsafe { MmapLines::open(&path).unwrap() };
+        assert_eq!(lines.lines().collect::<Vec<_>>(), vec![&b"one"[..], b"two"]);
+
+        drop(lines);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_unmappable_files() {
+        // procfs reports a length of zero, so this has to be read instead
+        let lines = unsafe { MmapLines::open("/proc/self/status").unwrap() };
+        let first = lines.lines().next().unwrap();
+
+        assert!(first.starts_with(b"Name:"));
+    }
+}
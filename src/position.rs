@@ -0,0 +1,32 @@
+//! Module exposing the positions of lines within input.
+
+/// Position of a line within the input.
+///
+/// Positions are tracked for every line read, which is useful when reporting
+/// errors found in the input:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let mut lines = ByteLines::new(&b"one\r\ntwo\n"[..]);
+/// let (line, position) = lines.next_with_position().unwrap().unwrap();
+///
+/// assert_eq!(line, b"one");
+/// assert_eq!(position, Position { line: 1, offset: 0, length: 5 });
+///
+/// let (line, position) = lines.next_with_position().unwrap().unwrap();
+///
+/// assert_eq!(line, b"two");
+/// assert_eq!(position, Position { line: 2, offset: 5, length: 4 });
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// The number of the line, starting from 1.
+    pub line: u64,
+
+    /// The byte offset of the start of the line in the input.
+    pub offset: u64,
+
+    /// The length of the line in the input, including the terminator.
+    pub length: u64,
+}
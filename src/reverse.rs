@@ -0,0 +1,259 @@
+//! Module exposing APIs based around reading lines in reverse.
+use crate::delim::{Delimiter, LineEnding};
+use crate::lending::LendingIterator;
+use crate::source::LineSource;
+use std::io::{Error, Read, Seek, SeekFrom};
+
+/// Default size of the blocks read from the end of the input.
+const DEFAULT_BLOCK_SIZE: usize = 8 * 1024;
+
+/// Provides iteration over bytes of input in reverse, split by line.
+///
+/// Input is read backwards from the end in blocks, so only the lines being
+/// walked are ever read, which makes this ideal for retrieving the last lines
+/// of a large file. Lines are split and stripped in the same way as the default
+/// handling of `ByteLines`, and are referenced from an internal buffer:
+///
+/// ```rust
+/// use bytelines::*;
+/// use std::fs::File;
+///
+/// // construct our iterator from our file input
+/// let file = File::open("./res/numbers.txt").unwrap();
+/// let mut lines = ReverseByteLines::new(file);
+///
+/// // walk our lines from the end using `while` syntax
+/// while let Some(line) = lines.next() {
+///     // do something with the line, which is Result<&[u8], _>
+/// }
+/// ```
+pub struct ReverseByteLines<R>
+where
+    R: Read + Seek,
+{
+    block: usize,
+    buffer: Vec<u8>,
+    head: usize,
+    end: usize,
+    position: Option<u64>,
+    reader: R,
+}
+
+impl<R> ReverseByteLines<R>
+where
+    R: Read + Seek,
+{
+    /// Constructs a new `ReverseByteLines` from an input `Read + Seek`.
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(DEFAULT_BLOCK_SIZE, reader)
+    }
+
+    /// Constructs a new `ReverseByteLines` reading blocks of the provided size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided block size is zero.
+    pub fn with_capacity(block: usize, reader: R) -> Self {
+        assert!(block > 0, "block size must be non-zero");
+        Self {
+            block,
+            buffer: Vec::new(),
+            head: 0,
+            end: 0,
+            position: None,
+            reader,
+        }
+    }
+
+    /// Retrieves a reference to the previous line of bytes in the reader (if any).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Result<&[u8], Error>> {
+        // bytes at the end of the buffer already known to contain no newline
+        let mut scanned = 0;
+
+        let start = loop {
+            // split after the previous newline, ignoring the terminator of this line
+            let trailing = match self.buffer[self.head..self.end].last() {
+                Some(b'\n') => 1,
+                _ => 0,
+            };
+            let body = &self.buffer[self.head..self.end - trailing - scanned];
+            if let Some(i) = body.iter().rposition(|b| *b == b'\n') {
+                break self.head + i + 1;
+            }
+            scanned = self.end - trailing - self.head;
+
+            // read the previous block, or take everything left at the start
+            match self.read_block() {
+                Ok(true) => continue,
+                Ok(false) if self.head == self.end => return None,
+                Ok(false) => break self.head,
+                Err(e) => return Some(Err(e)),
+            }
+        };
+
+        let line = &self.buffer[start..self.end];
+        let (len, _) = Delimiter::Line(LineEnding::default()).strip(line);
+
+        self.end = start;
+        Some(Ok(&line[..len]))
+    }
+
+    /// Retrieves the last `n` lines of the input, in their original order.
+    ///
+    /// ```rust
+    /// use bytelines::*;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("./res/numbers.txt").unwrap();
+    /// let lines = ReverseByteLines::new(file).tail(2).unwrap();
+    ///
+    /// assert_eq!(lines, vec![b"8", b"9"]);
+    /// ```
+    pub fn tail(&mut self, n: usize) -> Result<Vec<Vec<u8>>, Error> {
+        let mut lines = Vec::with_capacity(n);
+        while lines.len() < n {
+            match self.next() {
+                Some(line) => lines.push(line?.to_vec()),
+                None => break,
+            }
+        }
+        lines.reverse();
+        Ok(lines)
+    }
+
+    /// Reads the block preceding the buffer, returning whether anything was read.
+    fn read_block(&mut self) -> Result<bool, Error> {
+        let position = match self.position {
+            Some(position) => position,
+            None => self.reader.seek(SeekFrom::End(0))?,
+        };
+        self.position = Some(position);
+
+        if position == 0 {
+            return Ok(false);
+        }
+
+        let start = position.saturating_sub(self.block as u64);
+        let len = (position - start) as usize;
+
+        // make room in front of the buffer, doubling to keep long lines linear
+        if self.head < len {
+            let used = self.end - self.head;
+            let capacity = (used + len).max(used * 2);
+            let mut buffer = vec![0; capacity];
+
+            buffer[capacity - used..].copy_from_slice(&self.buffer[self.head..self.end]);
+
+            self.buffer = buffer;
+            self.head = capacity - used;
+            self.end = capacity;
+        }
+
+        // read the block in front of our current buffer
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.reader
+            .read_exact(&mut self.buffer[self.head - len..self.head])?;
+
+        self.head -= len;
+        self.position = Some(start);
+
+        Ok(true)
+    }
+}
+
+/// `LendingIterator` implementation for `ReverseByteLines`, to provide combinators.
+impl<R> LendingIterator for ReverseByteLines<R>
+where
+    R: Read + Seek,
+{
+    type Item<'a>
+        = Result<&'a [u8], Error>
+    where
+        Self: 'a;
+
+    /// Retrieves a reference to the previous line of bytes in the reader (if any).
+    #[inline]
+    fn next(&mut self) -> Option<Result<&[u8], Error>> {
+        ReverseByteLines::next(self)
+    }
+}
+
+/// `LineSource` implementation for `ReverseByteLines`.
+impl<R> LineSource for ReverseByteLines<R>
+where
+    R: Read + Seek,
+{
+    /// Retrieves a reference to the previous line of bytes in the reader (if any).
+    #[inline]
+    fn next_line(&mut self) -> Result<Option<&[u8]>, Error> {
+        self.next().transpose()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_range_loop)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_long_lines() {
+        let long = vec![b'x'; 100_000];
+        let mut input = b"first\n".to_vec();
+        input.extend_from_slice(&long);
+        input.extend_from_slice(b"\r\nlast\n");
+
+        for block in [1, 7, 4096] {
+            let mut brdr = ReverseByteLines::with_capacity(block, Cursor::new(&input));
+
+            assert_eq!(brdr.next().unwrap().unwrap(), b"last");
+            assert_eq!(brdr.next().unwrap().unwrap(), &long[..]);
+            assert_eq!(brdr.next().unwrap().unwrap(), b"first");
+            assert!(brdr.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_basic_loop() {
+        let file = File::open("./res/numbers.txt").unwrap();
+        let mut brdr = ReverseByteLines::with_capacity(3, file);
+        let mut lines = Vec::new();
+
+        while let Some(line) = brdr.next() {
+            let line = line.unwrap().to_vec();
+            let line = String::from_utf8(line).unwrap();
+
+            lines.push(line);
+        }
+
+        assert_eq!(lines.len(), 10);
+        for i in 0..10 {
+            assert_eq!(lines[i], format!("{}", 9 - i));
+        }
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let input = Cursor::new(&b"\none\r\n\r\ntwo\rthree"[..]);
+        let mut brdr = ReverseByteLines::with_capacity(2, input);
+        let mut lines = Vec::new();
+
+        while let Some(line) = brdr.next() {
+            lines.push(line.unwrap().to_vec());
+        }
+
+        assert_eq!(lines, vec![&b"two\rthree"[..], b"", b"one", b""]);
+    }
+
+    #[test]
+    fn test_tail() {
+        let input = Cursor::new(&b"one\ntwo\nthree\n"[..]);
+        let mut brdr = ReverseByteLines::new(input);
+
+        assert_eq!(brdr.tail(2).unwrap(), vec![&b"two"[..], b"three"]);
+        assert_eq!(brdr.tail(2).unwrap(), vec![&b"one"[..]]);
+        assert!(brdr.tail(2).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! Module exposing APIs based around in-memory byte slices.
+use crate::delim::{Delimiter, LineEnding};
+use crate::source::LineSource;
+use std::io::Error;
+use std::iter::FusedIterator;
+
+/// Provides iteration over lines of an in-memory byte slice.
+///
+/// As the input is already in memory, each line can reference the slice
+/// directly, so this structure implements the `Iterator` API without any
+/// allocations being required. Lines are split and stripped in the same
+/// way as the default handling of `ByteLines`:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let mut lines = bytelines::from_slice(b"one\r\ntwo\nthree");
+///
+/// assert_eq!(lines.next(), Some(&b"one"[..]));
+/// assert_eq!(lines.next_back(), Some(&b"three"[..]));
+/// assert_eq!(lines.next(), Some(&b"two"[..]));
+/// assert_eq!(lines.next(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SliceLines<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> SliceLines<'a> {
+    /// Constructs a new `SliceLines` from an input byte slice.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { remaining: bytes }
+    }
+}
+
+impl<'a> Iterator for SliceLines<'a> {
+    type Item = &'a [u8];
+
+    /// Retrieves the next line in the iterator (if any).
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+
+        // split after the next newline, or take everything left
+        let end = self
+            .remaining
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(self.remaining.len(), |i| i + 1);
+
+        let (line, remaining) = self.remaining.split_at(end);
+        self.remaining = remaining;
+        Some(strip(line))
+    }
+}
+
+impl<'a> DoubleEndedIterator for SliceLines<'a> {
+    /// Retrieves the next line from the back of the iterator (if any).
+    fn next_back(&mut self) -> Option<&'a [u8]> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+
+        // split after the previous newline, ignoring the terminator of the last line
+        let body = self.remaining.strip_suffix(b"\n").unwrap_or(self.remaining);
+        let start = body.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+
+        let (remaining, line) = self.remaining.split_at(start);
+        self.remaining = remaining;
+        Some(strip(line))
+    }
+}
+
+impl<'a> FusedIterator for SliceLines<'a> {}
+
+/// `LineSource` implementation for `SliceLines`, which can never fail.
+impl<'a> LineSource for SliceLines<'a> {
+    /// Retrieves a reference to the next line of bytes in the slice (if any).
+    #[inline]
+    fn next_line(&mut self) -> Result<Option<&[u8]>, Error> {
+        Ok(self.next())
+    }
+}
+
+/// Strips the line ending from a line using the default line ending policy.
+fn strip(line: &[u8]) -> &[u8] {
+    let (len, _) = Delimiter::Line(LineEnding::default()).strip(line);
+    &line[..len]
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_range_loop)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_basic_iterator() {
+        let input = fs::read("./res/numbers.txt").unwrap();
+        let lines = SliceLines::new(&input).collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 10);
+        for i in 0..10 {
+            assert_eq!(lines[i], format!("{}", i).as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_reverse_iterator() {
+        let input = &b"one\r\n\ntwo\r\n"[..];
+        let lines = SliceLines::new(input).rev().collect::<Vec<_>>();
+
+        assert_eq!(lines, vec![&b"two"[..], b"", b"one"]);
+    }
+
+    #[test]
+    fn test_empty_lines() {
+        assert_eq!(SliceLines::new(b"").count(), 0);
+        assert_eq!(SliceLines::new(b"").next_back(), None);
+        assert_eq!(SliceLines::new(b"\n").collect::<Vec<_>>(), vec![b""]);
+        assert_eq!(SliceLines::new(b"\n").rev().collect::<Vec<_>>(), vec![b""]);
+    }
+}
@@ -0,0 +1,91 @@
+//! Module exposing traits unifying the various sources of lines.
+use std::future::Future;
+use std::io::Error;
+use std::pin::Pin;
+
+/// Future returned when reading the next line from an `AsyncLineSource`.
+pub type NextLine<'a> = Pin<Box<dyn Future<Output = Result<Option<&'a [u8]>, Error>> + Send + 'a>>;
+
+/// Represents a source of lines which can be read synchronously.
+///
+/// This allows consumers to be written once over any source of lines in
+/// this crate, and is object safe to allow use via `Box<dyn LineSource>`:
+///
+/// ```rust
+/// use bytelines::*;
+/// use std::io::Error;
+///
+/// // a parser which works over any source of lines
+/// fn count(source: &mut dyn LineSource) -> Result<usize, Error> {
+///     let mut count = 0;
+///     while let Some(_) = source.next_line()? {
+///         count += 1;
+///     }
+///     Ok(count)
+/// }
+///
+/// assert_eq!(count(&mut ByteLines::new(&b"one\ntwo\n"[..])).unwrap(), 2);
+/// assert_eq!(count(&mut SliceLines::new(b"one\ntwo\n")).unwrap(), 2);
+/// ```
+///
+/// The shape of `next_line` matches that of `AsyncLineSource`, allowing for
+/// the same parsing logic to be shared between sync and async readers.
+pub trait LineSource {
+    /// Retrieves a reference to the next line of bytes in the source (if any).
+    fn next_line(&mut self) -> Result<Option<&[u8]>, Error>;
+}
+
+/// Represents a source of lines which can be read asynchronously.
+///
+/// This is the async counterpart to `LineSource`, and is also object safe
+/// to allow use via `Box<dyn AsyncLineSource>`. This comes at the cost of
+/// an allocation of the returned future for each line, so concrete types
+/// should be preferred where performance is paramount.
+pub trait AsyncLineSource {
+    /// Retrieves a reference to the next line of bytes in the source (if any).
+    fn next_line(&mut self) -> NextLine<'_>;
+}
+
+/// Forwarding implementation for mutable references to a `LineSource`.
+impl<S> LineSource for &mut S
+where
+    S: LineSource + ?Sized,
+{
+    #[inline]
+    fn next_line(&mut self) -> Result<Option<&[u8]>, Error> {
+        (**self).next_line()
+    }
+}
+
+/// Forwarding implementation for boxed `LineSource` values.
+impl<S> LineSource for Box<S>
+where
+    S: LineSource + ?Sized,
+{
+    #[inline]
+    fn next_line(&mut self) -> Result<Option<&[u8]>, Error> {
+        (**self).next_line()
+    }
+}
+
+/// Forwarding implementation for mutable references to an `AsyncLineSource`.
+impl<S> AsyncLineSource for &mut S
+where
+    S: AsyncLineSource + ?Sized,
+{
+    #[inline]
+    fn next_line(&mut self) -> NextLine<'_> {
+        (**self).next_line()
+    }
+}
+
+/// Forwarding implementation for boxed `AsyncLineSource` values.
+impl<S> AsyncLineSource for Box<S>
+where
+    S: AsyncLineSource + ?Sized,
+{
+    #[inline]
+    fn next_line(&mut self) -> NextLine<'_> {
+        (**self).next_line()
+    }
+}
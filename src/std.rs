@@ -1,12 +1,24 @@
 //! Module exposing APIs based around `BufRead` from stdlib.
-use std::io::{BufRead, Error};
+use crate::delim::{Delimiter, LineEnding, Terminator};
+use crate::lending::LendingIterator;
+use crate::limit::Overflow;
+use crate::position::Position;
+use crate::source::LineSource;
+use crate::text::StrLines;
+use crate::util::{Framer, Line, Status};
+use std::borrow::Cow;
+use std::io::{BufRead, Error, ErrorKind};
+use std::mem;
 
 /// Provides iteration over bytes of input, split by line.
 ///
 /// Unlike the implementation in the standard library, this requires
 /// no allocations and simply references the input lines from the
-/// internal buffer. In order to do this safely, we must sacrifice
-/// the `Iterator` API, and operate using `while` syntax:
+/// internal buffer. Lines which are fully contained in the buffer of
+/// the `BufRead` are referenced directly, and only lines straddling
+/// reads are copied into a buffer owned by this structure. In order to
+/// do this safely, we must sacrifice the `Iterator` API, and operate
+/// using `while` syntax:
 ///
 /// ```rust
 /// use bytelines::*;
@@ -49,8 +61,9 @@ pub struct ByteLines<B>
 where
     B: BufRead,
 {
-    buffer: Vec<u8>,
+    framer: Framer,
     reader: B,
+    borrowed: usize,
 }
 
 impl<B> ByteLines<B>
@@ -59,19 +72,279 @@ where
 {
     /// Constructs a new `ByteLines` from an input `BufRead`.
     pub fn new(buf: B) -> Self {
+        Self::with_delimiter(buf, Delimiter::default())
+    }
+
+    /// Constructs a new `ByteLines` from an input `BufRead`, splitting on a custom delimiter.
+    ///
+    /// The delimiter can be either a single byte or a sequence of bytes, and is
+    /// stripped from the end of each line. A leading `\r` is only stripped alongside
+    /// the delimiter when splitting on `\n`, so this can be used to walk NUL separated
+    /// input such as the output of `find -print0`:
+    ///
+    /// ```rust
+    /// use bytelines::*;
+    ///
+    /// let mut lines = ByteLines::with_delimiter(&b"one\0two\0"[..], b'\0');
+    ///
+    /// assert_eq!(lines.next().unwrap().unwrap(), b"one");
+    /// assert_eq!(lines.next().unwrap().unwrap(), b"two");
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn with_delimiter<D>(buf: B, delimiter: D) -> Self
+    where
+        D: Into<Delimiter>,
+    {
+        Self {
+            framer: Framer::new(delimiter.into()),
+            reader: buf,
+            borrowed: 0,
+        }
+    }
+
+    /// Constructs a new `ByteLines` from an input `BufRead` starting part way through the input.
+    ///
+    /// Positions of lines are reported from the provided line number and offset.
+    pub(crate) fn with_position(buf: B, start: Position) -> Self {
         Self {
-            buffer: Vec::new(),
+            framer: Framer::with_position(Delimiter::default(), start),
             reader: buf,
+            borrowed: 0,
         }
     }
 
+    /// Constructs a new `ByteLines` from an input `BufRead`, using a line ending policy.
+    ///
+    /// This is shorthand for calling `with_delimiter` with `Delimiter::Line`.
+    pub fn with_line_ending(buf: B, ending: LineEnding) -> Self {
+        Self::with_delimiter(buf, ending)
+    }
+
+    /// Sets the maximum length of a line, and how to handle lines exceeding it.
+    ///
+    /// This bounds the memory used to buffer each line, as reading would
+    /// otherwise continue until a delimiter is found in the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided maximum length is zero.
+    pub fn max_length(mut self, max: usize, overflow: Overflow) -> Self {
+        self.framer.limit(max, overflow);
+        self
+    }
+
+    /// Requires the final line of input to be terminated.
+    ///
+    /// An unterminated final line is reported as an error of kind `UnexpectedEof`
+    /// rather than being returned, which allows detecting input which was only
+    /// partially written. Without this, such a line can still be identified via
+    /// the `Terminator::None` returned by `next_with_ending`:
+    ///
+    /// ```rust
+    /// use bytelines::*;
+    /// use std::io::ErrorKind;
+    ///
+    /// let mut lines = ByteLines::new(&b"complete\npartial"[..]).strict();
+    ///
+    /// assert_eq!(lines.next().unwrap().unwrap(), b"complete");
+    /// assert_eq!(lines.next().unwrap().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn strict(mut self) -> Self {
+        self.framer.strict();
+        self
+    }
+
+    /// Attaches the position of the failed line to errors from the reader.
+    ///
+    /// By default errors from the underlying reader are returned as they are.
+    /// When enabled, they are instead wrapped in an `Error::Io` carrying the
+    /// line and offset being read, with the original error as its source:
+    ///
+    /// ```rust
+    /// use bytelines::*;
+    /// use std::io::{self, BufReader, ErrorKind, Read};
+    ///
+    /// struct Broken;
+    ///
+    /// impl Read for Broken {
+    ///     fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+    ///         Err(io::Error::new(ErrorKind::BrokenPipe, "broken"))
+    ///     }
+    /// }
+    ///
+    /// let input = (&b"one\n"[..]).chain(BufReader::new(Broken));
+    /// let mut lines = ByteLines::new(input).positioned_errors();
+    ///
+    /// assert_eq!(lines.next().unwrap().unwrap(), b"one");
+    ///
+    /// let err = lines.next().unwrap().unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    ///
+    /// let err = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+    /// assert!(matches!(err, Error::Io { line: 2, offset: 4, .. }));
+    /// ```
+    pub fn positioned_errors(mut self) -> Self {
+        self.framer.positioned();
+        self
+    }
+
     /// Retrieves a reference to the next line of bytes in the reader (if any).
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<Result<&[u8], Error>> {
-        self.buffer.clear();
-        crate::util::handle_line(
-            self.reader.read_until(b'\n', &mut self.buffer),
-            &mut self.buffer,
-        )
+        self.next_line().map(|r| r.map(|line| line.bytes))
+    }
+
+    /// Retrieves the next line of bytes in the reader, along with the terminator it ended with.
+    ///
+    /// The line is stripped in the same way as `next`, and the terminator can be used
+    /// to reconstruct the input exactly (such as when rewriting a file in place).
+    pub fn next_with_ending(&mut self) -> Option<Result<(&[u8], Terminator), Error>> {
+        self.next_line()
+            .map(|r| r.map(|line| (line.bytes, line.terminator)))
+    }
+
+    /// Retrieves the next line of bytes in the reader, along with its position in the input.
+    pub fn next_with_position(&mut self) -> Option<Result<(&[u8], Position), Error>> {
+        self.next_line()
+            .map(|r| r.map(|line| (line.bytes, line.position)))
+    }
+
+    /// Retrieves the next line in the reader as a `&str` (if any).
+    ///
+    /// The line is validated as UTF-8 without any allocation, and an error of kind
+    /// `InvalidData` is returned if it's invalid. The inner `Error::InvalidUtf8`
+    /// reports the offset of the first invalid byte in the input.
+    pub fn next_str(&mut self) -> Option<Result<&str, Error>> {
+        self.next_line().map(|r| r.and_then(|line| line.to_str()))
+    }
+
+    /// Retrieves the next line in the reader as a `Cow<str>` (if any).
+    ///
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD`, which requires an allocation.
+    /// Lines which are valid UTF-8 are borrowed as with `next_str`.
+    pub fn next_str_lossy(&mut self) -> Option<Result<Cow<'_, str>, Error>> {
+        self.next_line().map(|r| r.map(|line| line.to_str_lossy()))
+    }
+
+    /// Converts this wrapper to provide lines as `&str` via `StrLines`.
+    pub fn into_str_lines(self) -> StrLines<Self> {
+        StrLines::new(self)
+    }
+
+    /// Retrieves the position of the most recently read line in the input.
+    ///
+    /// This is the default `Position` until the first line has been read. After
+    /// an error, this is the position of the line which failed, up until the
+    /// point of failure.
+    pub fn position(&self) -> Position {
+        self.framer.position()
+    }
+
+    /// Retrieves a reference to the underlying reader.
+    ///
+    /// The most recently returned line may still be buffered in the reader, as
+    /// it is only consumed when the next line is requested.
+    pub fn get_ref(&self) -> &B {
+        &self.reader
+    }
+
+    /// Retrieves a mutable reference to the underlying reader.
+    ///
+    /// The most recently returned line is consumed from the reader beforehand,
+    /// so reading from the reader continues directly after the line.
+    pub fn get_mut(&mut self) -> &mut B {
+        self.reader.consume(mem::take(&mut self.borrowed));
+        &mut self.reader
+    }
+
+    /// Unwraps this `ByteLines`, returning the underlying reader.
+    ///
+    /// Any input following the most recently returned line remains available
+    /// from the reader, allowing a switch from lines to raw bytes mid-stream:
+    ///
+    /// ```rust
+    /// use bytelines::*;
+    /// use std::io::Read;
+    ///
+    /// let mut lines = ByteLines::new(&b"Length: 4\n\nBODY"[..]);
+    ///
+    /// assert_eq!(lines.next().unwrap().unwrap(), b"Length: 4");
+    /// assert_eq!(lines.next().unwrap().unwrap(), b"");
+    ///
+    /// let mut body = Vec::new();
+    /// lines.into_inner().read_to_end(&mut body).unwrap();
+    ///
+    /// assert_eq!(body, b"BODY");
+    /// ```
+    ///
+    /// Bytes of a partially read line are lost, which can only happen when a read
+    /// fails part way through a line, or after a line has been split due to its
+    /// length exceeding the configured maximum.
+    pub fn into_inner(mut self) -> B {
+        self.reader.consume(self.borrowed);
+        self.reader
+    }
+
+    /// Retrieves the next line in the reader, along with all associated metadata.
+    fn next_line(&mut self) -> Option<Result<Line<'_>, Error>> {
+        // release the line borrowed from the reader last time
+        self.reader.consume(mem::take(&mut self.borrowed));
+
+        // errors are reported as-is, except for interrupts which are retried
+        let available = match self.reader.fill_buf() {
+            Ok(available) => available,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => &[],
+            Err(e) => return self.framer.handle_line(Err(e)),
+        };
+
+        // reference the line directly when it's fully buffered in the reader
+        if let Some((used, len, terminator)) = self.framer.borrow(available) {
+            // we can't return `available` due to the borrow checker, but
+            // filling again returns the same buffer without any reads
+            self.borrowed = used;
+            return match self.reader.fill_buf() {
+                Ok(available) => Some(Ok(Line {
+                    bytes: &available[..len],
+                    terminator,
+                    position: self.framer.position(),
+                })),
+                Err(e) => self.framer.handle_line(Err(e)),
+            };
+        }
+
+        // otherwise we have to copy the line across reads
+        let status = read_line(&mut self.reader, &mut self.framer);
+        self.framer.handle_line(status)
+    }
+}
+
+/// `LendingIterator` implementation for `ByteLines`, to provide combinators.
+impl<B> LendingIterator for ByteLines<B>
+where
+    B: BufRead,
+{
+    type Item<'a>
+        = Result<&'a [u8], Error>
+    where
+        Self: 'a;
+
+    /// Retrieves a reference to the next line of bytes in the reader (if any).
+    #[inline]
+    fn next(&mut self) -> Option<Result<&[u8], Error>> {
+        ByteLines::next(self)
+    }
+}
+
+/// `LineSource` implementation for `ByteLines`.
+impl<B> LineSource for ByteLines<B>
+where
+    B: BufRead,
+{
+    /// Retrieves a reference to the next line of bytes in the reader (if any).
+    #[inline]
+    fn next_line(&mut self) -> Result<Option<&[u8]>, Error> {
+        self.next().transpose()
     }
 }
 
@@ -151,12 +424,39 @@ where
     }
 }
 
+/// Reads input into the framer until a line is available or EOF is reached.
+///
+/// This mirrors `BufRead::read_until`, except that delimiters are matched
+/// across chunks to allow for sequences straddling reads from the reader.
+fn read_line<B>(reader: &mut B, framer: &mut Framer) -> Result<Status, Error>
+where
+    B: BufRead,
+{
+    if let Some(status) = framer.resume() {
+        return Ok(status);
+    }
+    loop {
+        let (used, status) = {
+            let available = match reader.fill_buf() {
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            framer.feed(available)
+        };
+        reader.consume(used);
+        if let Some(status) = status {
+            return Ok(status);
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::needless_range_loop)]
 mod tests {
     use super::*;
     use std::fs::File;
-    use std::io::BufReader;
+    use std::io::{BufReader, Read};
 
     #[test]
     fn test_basic_loop() {
@@ -208,4 +508,433 @@ mod tests {
         assert_eq!(lines.len(), 1);
         assert_eq!(lines[0], "");
     }
+
+    #[test]
+    fn test_custom_delimiter() {
+        let input = &b"one\0two\r\0\0three"[..];
+        let lines = ByteLines::with_delimiter(input, b'\0')
+            .into_iter()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], b"one");
+        assert_eq!(lines[1], b"two\r");
+        assert_eq!(lines[2], b"");
+        assert_eq!(lines[3], b"three");
+    }
+
+    #[test]
+    fn test_sequence_delimiter() {
+        // use a tiny buffer to force delimiters across reads
+        let input = &b"one\r\n\r\ntwo\r\nstill two\r\n\r\nthree"[..];
+        let lines = ByteLines::with_delimiter(BufReader::with_capacity(2, input), b"\r\n\r\n")
+            .into_iter()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], b"one");
+        assert_eq!(lines[1], b"two\r\nstill two");
+        assert_eq!(lines[2], b"three");
+    }
+
+    #[test]
+    fn test_next_with_ending() {
+        let input = &b"one\rtwo\r\nthree\nfour"[..];
+        let mut brdr = ByteLines::with_line_ending(input, LineEnding::Any);
+        let mut lines = Vec::new();
+
+        while let Some(line) = brdr.next_with_ending() {
+            let (line, terminator) = line.unwrap();
+            lines.push((line.to_vec(), terminator));
+        }
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], (b"one".to_vec(), Terminator::Cr));
+        assert_eq!(lines[1], (b"two".to_vec(), Terminator::Crlf));
+        assert_eq!(lines[2], (b"three".to_vec(), Terminator::Lf));
+        assert_eq!(lines[3], (b"four".to_vec(), Terminator::None));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        struct Flaky(Vec<Error>, &'static [u8]);
+
+        impl Read for Flaky {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.pop() {
+                    Some(err) => Err(err),
+                    None => self.1.read(buf),
+                }
+            }
+        }
+
+        let errors = vec![
+            Error::from(ErrorKind::TimedOut),
+            Error::from(ErrorKind::Interrupted),
+        ];
+        let mut lines = BufReader::new(Flaky(errors, b"one\n")).byte_lines();
+
+        assert_eq!(
+            lines.next().unwrap().unwrap_err().kind(),
+            ErrorKind::TimedOut
+        );
+        assert_eq!(lines.next().unwrap().unwrap(), b"one");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_error_context() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(Error::from_raw_os_error(32))
+            }
+        }
+
+        let input = (&b"one\r\nabcdefghij\r\ntw"[..]).chain(Failing);
+        let mut lines = BufReader::with_capacity(4, input)
+            .byte_lines()
+            .max_length(3, Overflow::Error);
+
+        assert_eq!(lines.next().unwrap().unwrap(), b"one");
+
+        let err = lines.next().unwrap().unwrap_err();
+        let inner = err.get_ref().unwrap().downcast_ref::<crate::Error>();
+
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(matches!(
+            inner,
+            Some(crate::Error::LineTooLong { line: 2, offset: 5 })
+        ));
+
+        // reader errors are passed through untouched
+        let err = lines.next().unwrap().unwrap_err();
+
+        assert_eq!(err.raw_os_error(), Some(32));
+        assert_eq!(
+            lines.position(),
+            Position {
+                line: 3,
+                offset: 17,
+                length: 2
+            }
+        );
+
+        // unless positioned errors are requested
+        let input = (&b"one\nabcdefghij\ntw"[..]).chain(Failing);
+        let mut lines = BufReader::with_capacity(4, input)
+            .byte_lines()
+            .max_length(3, Overflow::Error)
+            .positioned_errors();
+
+        assert_eq!(lines.next().unwrap().unwrap(), b"one");
+
+        let err = lines.next().unwrap().unwrap_err();
+        let inner = err.get_ref().unwrap().downcast_ref::<crate::Error>();
+
+        assert!(matches!(
+            inner,
+            Some(crate::Error::LineTooLong { line: 2, offset: 4 })
+        ));
+
+        let err = lines.next().unwrap().unwrap_err();
+        let inner = err.get_ref().unwrap().downcast_ref::<crate::Error>();
+
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+        match inner {
+            Some(crate::Error::Io {
+                source,
+                line,
+                offset,
+            }) => {
+                assert_eq!(source.raw_os_error(), Some(32));
+                assert_eq!((*line, *offset), (3, 15));
+            }
+            _ => panic!("expected a positioned I/O error"),
+        }
+    }
+
+    #[test]
+    fn test_strict() {
+        let collect = |input: &[u8], overflow| {
+            let mut lines = Vec::new();
+
+            for capacity in 1..=input.len().max(1) {
+                let mut brdr = BufReader::with_capacity(capacity, input)
+                    .byte_lines()
+                    .max_length(3, overflow)
+                    .strict();
+                let mut found = Vec::new();
+
+                while let Some(line) = brdr.next() {
+                    found.push(line.map(|line| line.to_vec()).map_err(|e| e.kind()));
+                }
+
+                lines.push(found);
+            }
+
+            lines.dedup();
+            assert_eq!(lines.len(), 1);
+            lines.remove(0)
+        };
+
+        assert_eq!(collect(b"", Overflow::Error), vec![]);
+        assert_eq!(collect(b"\n", Overflow::Error), vec![Ok(vec![])]);
+        assert_eq!(
+            collect(b"ab\r\n", Overflow::Error),
+            vec![Ok(b"ab".to_vec())]
+        );
+
+        let lines = collect(b"ab\nab", Overflow::Error);
+        assert_eq!(lines[0], Ok(b"ab".to_vec()));
+        assert_eq!(lines[1], Err(ErrorKind::UnexpectedEof));
+        assert_eq!(lines.len(), 2);
+
+        let lines = collect(b"ab\nab\r", Overflow::Error);
+        assert_eq!(lines[1], Err(ErrorKind::UnexpectedEof));
+        assert_eq!(lines.len(), 2);
+
+        let lines = collect(b"abcdef", Overflow::Truncate);
+        assert_eq!(lines, vec![Err(ErrorKind::UnexpectedEof)]);
+
+        let lines = collect(b"abcdef", Overflow::Split);
+        assert_eq!(lines[0], Ok(b"abc".to_vec()));
+        assert_eq!(lines[1], Err(ErrorKind::UnexpectedEof));
+        assert_eq!(lines.len(), 2);
+
+        let err = BufReader::new(&b"one\ntwo"[..])
+            .byte_lines()
+            .strict()
+            .skip(1)
+            .next()
+            .unwrap()
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "line is missing a terminator (line 2, offset 4)"
+        );
+    }
+
+    #[test]
+    fn test_max_length() {
+        let input = &b"abc\r\nabcd\r\nabcdefghij\r\nab"[..];
+        let collect = |overflow| {
+            let mut brdr = BufReader::with_capacity(3, input)
+                .byte_lines()
+                .max_length(3, overflow);
+            let mut lines = Vec::new();
+
+            while let Some(line) = brdr.next() {
+                lines.push(line.map(|line| line.to_vec()).map_err(|e| e.kind()));
+            }
+
+            lines
+        };
+
+        let lines = collect(Overflow::Error);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], Ok(b"abc".to_vec()));
+        assert_eq!(lines[1], Err(ErrorKind::InvalidData));
+        assert_eq!(lines[2], Err(ErrorKind::InvalidData));
+        assert_eq!(lines[3], Ok(b"ab".to_vec()));
+
+        let lines = collect(Overflow::Truncate);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], Ok(b"abc".to_vec()));
+        assert_eq!(lines[1], Ok(b"abc".to_vec()));
+        assert_eq!(lines[2], Ok(b"abc".to_vec()));
+        assert_eq!(lines[3], Ok(b"ab".to_vec()));
+
+        let lines = collect(Overflow::Split);
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], Ok(b"abc".to_vec()));
+        assert_eq!(lines[1], Ok(b"abc".to_vec()));
+        assert_eq!(lines[2], Ok(b"d".to_vec()));
+        assert_eq!(lines[3], Ok(b"abc".to_vec()));
+        assert_eq!(lines[4], Ok(b"def".to_vec()));
+        assert_eq!(lines[5], Ok(b"ghi".to_vec()));
+        assert_eq!(lines[6], Ok(b"j".to_vec()));
+        assert_eq!(lines[7], Ok(b"ab".to_vec()));
+    }
+
+    #[test]
+    fn test_borrowed_lines() {
+        let input = &b"one\ntwo\nthree"[..];
+        let range = input.as_ptr_range();
+        let mut brdr = input.byte_lines();
+
+        // complete lines are referenced directly from the input
+        let line = brdr.next().unwrap().unwrap();
+        assert_eq!(line, b"one");
+        assert!(range.contains(&line.as_ptr()));
+
+        let line = brdr.next().unwrap().unwrap();
+        assert_eq!(line, b"two");
+        assert!(range.contains(&line.as_ptr()));
+
+        // unterminated lines have to be copied to detect EOF
+        let line = brdr.next().unwrap().unwrap();
+        assert_eq!(line, b"three");
+        assert!(!range.contains(&line.as_ptr()));
+
+        assert!(brdr.next().is_none());
+
+        // lines after a line straddling two reads are still referenced directly
+        let (head, tail) = (&b"one\ntw"[..], &b"o\nthree\nfour\n"[..]);
+        let range = tail.as_ptr_range();
+        let mut brdr = head.chain(tail).byte_lines();
+
+        assert_eq!(brdr.next().unwrap().unwrap(), b"one");
+        assert_eq!(brdr.next().unwrap().unwrap(), b"two");
+
+        for expected in [&b"three"[..], b"four"] {
+            let line = brdr.next().unwrap().unwrap();
+            assert_eq!(line, expected);
+            assert!(range.contains(&line.as_ptr()));
+        }
+
+        assert!(brdr.next().is_none());
+    }
+
+    #[test]
+    fn test_borrowed_straddling_lines() {
+        let input = &b"one\ntwo\r\nthree\nfour\n"[..];
+        let lines = BufReader::with_capacity(5, input)
+            .byte_lines()
+            .into_iter()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, vec![&b"one"[..], b"two", b"three", b"four"]);
+    }
+
+    #[test]
+    fn test_next_with_position() {
+        let input = &b"one\r\n\ntoo long\nthree"[..];
+        let mut brdr = BufReader::with_capacity(4, input)
+            .byte_lines()
+            .max_length(5, Overflow::Error);
+
+        let (line, position) = brdr.next_with_position().unwrap().unwrap();
+        assert_eq!(line, b"one");
+        assert_eq!((position.line, position.offset, position.length), (1, 0, 5));
+
+        let (line, position) = brdr.next_with_position().unwrap().unwrap();
+        assert_eq!(line, b"");
+        assert_eq!((position.line, position.offset, position.length), (2, 5, 1));
+
+        assert!(brdr.next_with_position().unwrap().is_err());
+        assert_eq!((brdr.position().line, brdr.position().offset), (3, 6));
+
+        let (line, position) = brdr.next_with_position().unwrap().unwrap();
+        assert_eq!(line, b"three");
+        assert_eq!(
+            (position.line, position.offset, position.length),
+            (4, 15, 5)
+        );
+
+        assert!(brdr.next_with_position().is_none());
+    }
+
+    #[test]
+    fn test_line_source() {
+        let mut sources: Vec<Box<dyn LineSource>> = vec![
+            Box::new(ByteLines::new(&b"one\ntwo"[..])),
+            Box::new(crate::from_slice(b"one\ntwo")),
+        ];
+
+        for source in &mut sources {
+            assert_eq!(source.next_line().unwrap(), Some(&b"one"[..]));
+            assert_eq!(source.next_line().unwrap(), Some(&b"two"[..]));
+            assert_eq!(source.next_line().unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let input = &b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n\x00\x01\n\x02"[..];
+
+        for capacity in 1..=input.len() {
+            let reader = BufReader::with_capacity(capacity, input);
+            let mut lines = reader.byte_lines();
+
+            assert_eq!(lines.next().unwrap().unwrap(), b"GET / HTTP/1.1");
+            assert_eq!(lines.next().unwrap().unwrap(), b"Host: localhost");
+            assert_eq!(lines.next().unwrap().unwrap(), b"");
+
+            let mut body = Vec::new();
+            lines.into_inner().read_to_end(&mut body).unwrap();
+
+            assert_eq!(body, b"\x00\x01\n\x02");
+        }
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let reader = BufReader::new(&b"one\ntwo\nthree\n"[..]);
+        let mut lines = reader.byte_lines();
+
+        assert_eq!(lines.next().unwrap().unwrap(), b"one");
+
+        let mut byte = [0; 1];
+        lines.get_mut().read_exact(&mut byte).unwrap();
+
+        assert_eq!(&byte, b"t");
+        assert_eq!(lines.next().unwrap().unwrap(), b"wo");
+        assert_eq!(lines.next().unwrap().unwrap(), b"three");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_lending_iterator() {
+        let input = &b"1\n\n2\nthree\n4\n\n5\n"[..];
+        let lines = || BufReader::with_capacity(2, input).byte_lines();
+
+        assert_eq!(lines().count(), 7);
+        assert_eq!(lines().skip(2).take(3).count(), 3);
+        assert_eq!(lines().skip(10).count(), 0);
+        assert_eq!(
+            lines()
+                .filter(|line| !line.as_ref().unwrap().is_empty())
+                .count(),
+            5
+        );
+
+        let numbers = lines()
+            .filter(|line| !line.as_ref().unwrap().is_empty())
+            .map_while(|line| std::str::from_utf8(line.unwrap()).ok()?.parse::<u8>().ok())
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, vec![1, 2]);
+
+        let mut longest = 0;
+        lines().for_each(|line| longest = longest.max(line.unwrap().len()));
+        assert_eq!(longest, 5);
+
+        let total = lines().try_fold(0, |total, line| line.map(|line| total + line.len()));
+        assert_eq!(total.unwrap(), 9);
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let input = &b"one\rtwo\r\nthree\r\rfour\n"[..];
+        let collect = |ending| {
+            ByteLines::with_line_ending(BufReader::with_capacity(1, input), ending)
+                .into_iter()
+                .map(|line| line.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let lines = collect(LineEnding::Any);
+        assert_eq!(lines, vec![&b"one"[..], b"two", b"three", b"", b"four"]);
+
+        let lines = collect(LineEnding::Crlf);
+        assert_eq!(lines, vec![&b"one\rtwo"[..], b"three\r\rfour\n"]);
+
+        let lines = collect(LineEnding::Keep);
+        assert_eq!(lines, vec![&b"one\rtwo\r\n"[..], b"three\r\rfour\n"]);
+    }
 }
@@ -0,0 +1,141 @@
+//! Module exposing APIs to read lines of input as UTF-8 text.
+use crate::lending::LendingIterator;
+use crate::std::ByteLines;
+use std::borrow::Cow;
+use std::io::{BufRead, Error};
+
+/// Provides iteration over lines of input as `&str`, via a line reader.
+///
+/// Lines are validated as UTF-8 in place, so (unlike `BufRead::lines`) no
+/// allocation is required per line. This is constructed from `ByteLines` or
+/// `AsyncByteLines` via their `into_str_lines` methods:
+///
+/// ```rust
+/// use bytelines::*;
+///
+/// let mut lines = ByteLines::new(&b"caf\xc3\xa9\ncaf\xe9\n"[..]).into_str_lines();
+///
+/// assert_eq!(lines.next().unwrap().unwrap(), "café");
+///
+/// let err = lines.next().unwrap().unwrap_err();
+/// let err = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+///
+/// assert!(matches!(err, Error::InvalidUtf8 { line: 2, offset: 9 }));
+/// ```
+///
+/// Invalid input can instead be replaced with `U+FFFD` via `next_lossy`,
+/// which only allocates for lines which are not valid UTF-8.
+pub struct StrLines<L> {
+    lines: L,
+}
+
+impl<L> StrLines<L> {
+    /// Constructs a new `StrLines` from a line reader.
+    pub(crate) fn new(lines: L) -> Self {
+        Self { lines }
+    }
+
+    /// Retrieves a reference to the underlying line reader.
+    pub fn get_ref(&self) -> &L {
+        &self.lines
+    }
+
+    /// Retrieves a mutable reference to the underlying line reader.
+    pub fn get_mut(&mut self) -> &mut L {
+        &mut self.lines
+    }
+
+    /// Unwraps this `StrLines`, returning the underlying line reader.
+    pub fn into_inner(self) -> L {
+        self.lines
+    }
+}
+
+impl<B> StrLines<ByteLines<B>>
+where
+    B: BufRead,
+{
+    /// Retrieves the next line in the reader as a `&str` (if any).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Result<&str, Error>> {
+        self.lines.next_str()
+    }
+
+    /// Retrieves the next line in the reader as a `Cow<str>`, replacing invalid UTF-8.
+    pub fn next_lossy(&mut self) -> Option<Result<Cow<'_, str>, Error>> {
+        self.lines.next_str_lossy()
+    }
+}
+
+/// `LendingIterator` implementation for `StrLines`, to provide combinators.
+impl<B> LendingIterator for StrLines<ByteLines<B>>
+where
+    B: BufRead,
+{
+    type Item<'a>
+        = Result<&'a str, Error>
+    where
+        Self: 'a;
+
+    /// Retrieves the next line in the reader as a `&str` (if any).
+    #[inline]
+    fn next(&mut self) -> Option<Result<&str, Error>> {
+        self.lines.next_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ByteLinesReader;
+    use std::io::{BufReader, ErrorKind};
+
+    #[test]
+    fn test_str_lines() {
+        let input = &b"caf\xc3\xa9\r\n\xff\xfe\nok\n"[..];
+
+        for capacity in 1..=input.len() {
+            let mut lines = BufReader::with_capacity(capacity, input)
+                .byte_lines()
+                .into_str_lines();
+
+            assert_eq!(lines.next().unwrap().unwrap(), "café");
+
+            let err = lines.next().unwrap().unwrap_err();
+            let inner = err.get_ref().unwrap().downcast_ref::<crate::Error>();
+
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            assert!(matches!(
+                inner,
+                Some(crate::Error::InvalidUtf8 { line: 2, offset: 7 })
+            ));
+
+            assert_eq!(lines.next().unwrap().unwrap(), "ok");
+            assert!(lines.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_str_lines_lossy() {
+        let input = &b"caf\xc3\xa9\nca\xffe\n"[..];
+        let mut lines = ByteLines::new(input).into_str_lines();
+
+        let line = lines.next_lossy().unwrap().unwrap();
+        assert!(matches!(line, Cow::Borrowed("café")));
+
+        let line = lines.next_lossy().unwrap().unwrap();
+        assert_eq!(line, "ca\u{fffd}e");
+        assert!(lines.next_lossy().is_none());
+    }
+
+    #[test]
+    fn test_invalid_utf8_offset() {
+        let mut lines = ByteLines::new(&b"one\ntw\xc3o\n"[..]);
+
+        assert_eq!(lines.next_str().unwrap().unwrap(), "one");
+        assert_eq!(
+            lines.next_str().unwrap().unwrap_err().to_string(),
+            "line contains invalid UTF-8 (line 2, offset 6)"
+        );
+    }
+}
@@ -1,8 +1,20 @@
 //! Module exposing APIs based around `AsyncBufRead` from Tokio.
+use bytes::{Bytes, BytesMut};
+use futures_util::ready;
 use futures_util::stream::{self, Stream};
 use tokio::io::{AsyncBufRead, AsyncBufReadExt};
 
+use crate::codec::ByteLinesCodec;
+use crate::delim::{Delimiter, LineEnding, Terminator};
+use crate::limit::Overflow;
+use crate::position::Position;
+use crate::source::{AsyncLineSource, NextLine};
+use crate::text::StrLines;
+use crate::util::{Framer, Line, Status};
+use std::borrow::Cow;
 use std::io::Error;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 /// Provides async iteration over bytes of input, split by line.
 ///
@@ -20,6 +32,7 @@ use std::io::Error;
 /// while let Some(line) = lines.next().await? {
 ///     // do something with the line, which is &[u8]
 /// }
+/// ```
 ///
 /// This differs from the `stdlib` version of the API as it fits
 /// more closely with the Tokio API for types.
@@ -45,11 +58,18 @@ use std::io::Error;
 ///
 /// });
 /// ```
+///
+/// # Cancel safety
+///
+/// The `next` methods of this structure are cancellation safe, so they can
+/// be used as a branch of `tokio::select!`. If a future is dropped before
+/// a line is returned, any part of the line already read is kept, and will
+/// be completed by the next call.
 pub struct AsyncByteLines<B>
 where
     B: AsyncBufRead + Unpin,
 {
-    buffer: Vec<u8>,
+    framer: Framer,
     reader: B,
 }
 
@@ -57,22 +77,150 @@ impl<B> AsyncByteLines<B>
 where
     B: AsyncBufRead + Unpin,
 {
-    /// Constructs a new `ByteLines` from an input `AsyncBufRead`.
+    /// Constructs a new `AsyncByteLines` from an input `AsyncBufRead`.
     pub fn new(buf: B) -> Self {
+        Self::with_delimiter(buf, Delimiter::default())
+    }
+
+    /// Constructs a new `AsyncByteLines` from an input `AsyncBufRead`, splitting on a custom delimiter.
+    ///
+    /// The delimiter can be either a single byte or a sequence of bytes, and is
+    /// stripped from the end of each line. A leading `\r` is only stripped alongside
+    /// the delimiter when splitting on `\n`.
+    pub fn with_delimiter<D>(buf: B, delimiter: D) -> Self
+    where
+        D: Into<Delimiter>,
+    {
         Self {
-            buffer: Vec::new(),
+            framer: Framer::new(delimiter.into()),
             reader: buf,
         }
     }
 
+    /// Constructs a new `AsyncByteLines` from an input `AsyncBufRead`, using a line ending policy.
+    ///
+    /// This is shorthand for calling `with_delimiter` with `Delimiter::Line`.
+    pub fn with_line_ending(buf: B, ending: LineEnding) -> Self {
+        Self::with_delimiter(buf, ending)
+    }
+
+    /// Sets the maximum length of a line, and how to handle lines exceeding it.
+    ///
+    /// This bounds the memory used to buffer each line, as reading would
+    /// otherwise continue until a delimiter is found in the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided maximum length is zero.
+    pub fn max_length(mut self, max: usize, overflow: Overflow) -> Self {
+        self.framer.limit(max, overflow);
+        self
+    }
+
+    /// Requires the final line of input to be terminated.
+    ///
+    /// An unterminated final line is reported as an error of kind `UnexpectedEof`
+    /// rather than being returned, which allows detecting input which was only
+    /// partially written. Without this, such a line can still be identified via
+    /// the `Terminator::None` returned by `next_with_ending`.
+    pub fn strict(mut self) -> Self {
+        self.framer.strict();
+        self
+    }
+
+    /// Attaches the position of the failed line to errors from the reader.
+    ///
+    /// By default errors from the underlying reader are returned as they are.
+    /// When enabled, they are instead wrapped in an `Error::Io` carrying the
+    /// line and offset being read, with the original error as its source.
+    pub fn positioned_errors(mut self) -> Self {
+        self.framer.positioned();
+        self
+    }
+
     /// Retrieves a reference to the next line of bytes in the reader (if any).
+    ///
+    /// This method is cancellation safe; partial lines survive a dropped future.
     pub async fn next(&mut self) -> Result<Option<&[u8]>, Error> {
-        self.buffer.clear();
-        let handled = crate::util::handle_line(
-            self.reader.read_until(b'\n', &mut self.buffer).await,
-            &mut self.buffer,
-        );
-        handled.transpose()
+        Ok(self.next_line().await?.map(|line| line.bytes))
+    }
+
+    /// Retrieves the next line of bytes in the reader, along with the terminator it ended with.
+    ///
+    /// The line is stripped in the same way as `next`, and the terminator can be used
+    /// to reconstruct the input exactly (such as when rewriting a file in place).
+    pub async fn next_with_ending(&mut self) -> Result<Option<(&[u8], Terminator)>, Error> {
+        let line = self.next_line().await?;
+        Ok(line.map(|line| (line.bytes, line.terminator)))
+    }
+
+    /// Retrieves the next line of bytes in the reader, along with its position in the input.
+    pub async fn next_with_position(&mut self) -> Result<Option<(&[u8], Position)>, Error> {
+        let line = self.next_line().await?;
+        Ok(line.map(|line| (line.bytes, line.position)))
+    }
+
+    /// Retrieves the next line in the reader as a `&str` (if any).
+    ///
+    /// The line is validated as UTF-8 without any allocation, and an error of kind
+    /// `InvalidData` is returned if it's invalid. The inner `Error::InvalidUtf8`
+    /// reports the offset of the first invalid byte in the input.
+    pub async fn next_str(&mut self) -> Result<Option<&str>, Error> {
+        let line = self.next_line().await?;
+        line.map(|line| line.to_str()).transpose()
+    }
+
+    /// Retrieves the next line in the reader as a `Cow<str>` (if any).
+    ///
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD`, which requires an allocation.
+    /// Lines which are valid UTF-8 are borrowed as with `next_str`.
+    pub async fn next_str_lossy(&mut self) -> Result<Option<Cow<'_, str>>, Error> {
+        let line = self.next_line().await?;
+        Ok(line.map(|line| line.to_str_lossy()))
+    }
+
+    /// Converts this wrapper to provide lines as `&str` via `StrLines`.
+    pub fn into_str_lines(self) -> StrLines<Self> {
+        StrLines::new(self)
+    }
+
+    /// Retrieves the position of the most recently read line in the input.
+    ///
+    /// This is the default `Position` until the first line has been read. After
+    /// an error, this is the position of the line which failed, up until the
+    /// point of failure.
+    pub fn position(&self) -> Position {
+        self.framer.position()
+    }
+
+    /// Retrieves a reference to the underlying reader.
+    pub fn get_ref(&self) -> &B {
+        &self.reader
+    }
+
+    /// Retrieves a mutable reference to the underlying reader.
+    ///
+    /// Reading from the reader continues directly after the most recently returned line.
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.reader
+    }
+
+    /// Unwraps this `AsyncByteLines`, returning the underlying reader.
+    ///
+    /// Any input following the most recently returned line remains available
+    /// from the reader, allowing a switch from lines to raw bytes mid-stream.
+    ///
+    /// Bytes of a partially read line are lost, which can only happen when a read
+    /// fails (or is cancelled) part way through a line, or after a line has been
+    /// split due to its length exceeding the configured maximum.
+    pub fn into_inner(self) -> B {
+        self.reader
+    }
+
+    /// Retrieves the next line in the reader, along with all associated metadata.
+    async fn next_line(&mut self) -> Result<Option<Line<'_>>, Error> {
+        let status = read_line(&mut self.reader, &mut self.framer).await;
+        self.framer.handle_line(status).transpose()
     }
 
     /// Converts this wrapper to provide a `Stream` API.
@@ -87,11 +235,209 @@ where
     }
 }
 
+impl<B> StrLines<AsyncByteLines<B>>
+where
+    B: AsyncBufRead + Unpin,
+{
+    /// Retrieves the next line in the reader as a `&str` (if any).
+    pub async fn next(&mut self) -> Result<Option<&str>, Error> {
+        self.get_mut().next_str().await
+    }
+
+    /// Retrieves the next line in the reader as a `Cow<str>`, replacing invalid UTF-8.
+    pub async fn next_lossy(&mut self) -> Result<Option<Cow<'_, str>>, Error> {
+        self.get_mut().next_str_lossy().await
+    }
+}
+
+/// `AsyncLineSource` implementation for `AsyncByteLines`.
+impl<B> AsyncLineSource for AsyncByteLines<B>
+where
+    B: AsyncBufRead + Send + Unpin,
+{
+    /// Retrieves a reference to the next line of bytes in the reader (if any).
+    #[inline]
+    fn next_line(&mut self) -> NextLine<'_> {
+        Box::pin(self.next())
+    }
+}
+
+/// Represents anything which can provide async iterators of byte lines.
+///
+/// This mirrors `ByteLinesReader`, so that both sync and async readers can
+/// be walked in the same way:
+///
+/// ```rust ignore
+/// use bytelines::AsyncByteLinesReader;
+/// use tokio::fs::File;
+/// use tokio::io::BufReader;
+///
+/// // construct our iterator from our file input
+/// let file = File::open("./res/numbers.txt").await?;
+/// let mut lines = BufReader::new(file).async_byte_lines();
+///
+/// // walk our lines using `while` syntax
+/// while let Some(line) = lines.next().await? {
+///     // do something with the line, which is &[u8]
+/// }
+/// ```
+///
+/// The method is named differently to `ByteLinesReader::byte_lines`, as types
+/// such as `&[u8]` implement both `BufRead` and `AsyncBufRead`.
+pub trait AsyncByteLinesReader<B>
+where
+    B: AsyncBufRead + Unpin,
+{
+    /// Returns a structure used to iterate the lines of this reader as `Result<Option<&[u8]>, _>`.
+    fn async_byte_lines(self) -> AsyncByteLines<B>;
+}
+
+/// Blanket implementation for all `AsyncBufRead`.
+impl<B> AsyncByteLinesReader<B> for B
+where
+    B: AsyncBufRead + Unpin,
+{
+    /// Returns a structure used to iterate the lines of this reader as `Result<Option<&[u8]>, _>`.
+    #[inline]
+    fn async_byte_lines(self) -> AsyncByteLines<Self> {
+        super::from_tokio(self)
+    }
+}
+
+/// Provides a `Stream` of owned lines of input, as `Bytes`.
+///
+/// Input is read into a shared `BytesMut`, and each line is split off the
+/// front of it. This means lines are cheap to clone and can be held across
+/// await points, without the allocation of a `Vec` per line:
+///
+/// ```rust ignore
+/// use bytelines::*;
+/// use tokio::fs::File;
+/// use tokio::io::BufReader;
+///
+/// // construct our stream from our file input
+/// let file = File::open("./res/numbers.txt").await?;
+/// let reader = BufReader::new(file);
+/// let mut lines = ByteLinesStream::new(reader);
+///
+/// // walk our lines using `Stream` syntax
+/// while let Some(line) = lines.next().await {
+///     // do something with the line, which is Result<Bytes, _>
+/// }
+/// ```
+///
+/// Lines are split and stripped in the same way as `AsyncByteLines`.
+pub struct ByteLinesStream<B>
+where
+    B: AsyncBufRead + Unpin,
+{
+    buffer: BytesMut,
+    codec: ByteLinesCodec,
+    eof: bool,
+    reader: B,
+}
+
+impl<B> ByteLinesStream<B>
+where
+    B: AsyncBufRead + Unpin,
+{
+    /// Constructs a new `ByteLinesStream` from an input `AsyncBufRead`.
+    pub fn new(buf: B) -> Self {
+        Self::with_delimiter(buf, Delimiter::default())
+    }
+
+    /// Constructs a new `ByteLinesStream` from an input `AsyncBufRead`, splitting on a custom delimiter.
+    pub fn with_delimiter<D>(buf: B, delimiter: D) -> Self
+    where
+        D: Into<Delimiter>,
+    {
+        Self {
+            buffer: BytesMut::new(),
+            codec: ByteLinesCodec::new(delimiter.into()),
+            eof: false,
+            reader: buf,
+        }
+    }
+
+    /// Sets the maximum length of a line, and how to handle lines exceeding it.
+    ///
+    /// This bounds the memory used to buffer each line, as reading would
+    /// otherwise continue until a delimiter is found in the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided maximum length is zero.
+    pub fn max_length(mut self, max: usize, overflow: Overflow) -> Self {
+        self.codec.max_length(max, overflow);
+        self
+    }
+}
+
+/// `Stream` implementation of `ByteLinesStream`, yielding `Bytes`.
+impl<B> Stream for ByteLinesStream<B>
+where
+    B: AsyncBufRead + Unpin,
+{
+    type Item = Result<Bytes, Error>;
+
+    /// Polls for the next line in the stream (if any).
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            // no more input, so flush whatever is left
+            if this.eof {
+                return Poll::Ready(this.codec.decode_eof(&mut this.buffer).transpose());
+            }
+
+            // check for a full line in what we already have
+            if let Some(line) = this.codec.decode(&mut this.buffer).transpose() {
+                return Poll::Ready(Some(line));
+            }
+
+            // otherwise we have to read some more input
+            let reader = Pin::new(&mut this.reader);
+            let available = match ready!(reader.poll_fill_buf(cx)) {
+                Ok(available) => available,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+
+            let used = available.len();
+            this.buffer.extend_from_slice(available);
+            this.eof = used == 0;
+
+            Pin::new(&mut this.reader).consume(used);
+        }
+    }
+}
+
+/// Reads input into the framer until a line is available or EOF is reached.
+///
+/// This mirrors `AsyncBufReadExt::read_until`, except that delimiters are
+/// matched across chunks to allow for sequences straddling reads.
+async fn read_line<B>(reader: &mut B, framer: &mut Framer) -> Result<Status, Error>
+where
+    B: AsyncBufRead + Unpin,
+{
+    if let Some(status) = framer.resume() {
+        return Ok(status);
+    }
+    loop {
+        let (used, status) = {
+            let available = reader.fill_buf().await?;
+            framer.feed(available)
+        };
+        reader.consume(used);
+        if let Some(status) = status {
+            return Ok(status);
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::needless_range_loop)]
 mod tests {
     use tokio::fs::File;
-    use tokio::io::BufReader;
+    use tokio::io::{AsyncReadExt, BufReader};
 
     #[tokio::test]
     async fn test_basic_loop() {
@@ -129,4 +475,255 @@ mod tests {
             assert_eq!(lines[i], format!("{}", i));
         }
     }
+
+    #[tokio::test]
+    async fn test_reader_extension() {
+        use crate::AsyncByteLinesReader;
+
+        let file = File::open("./res/numbers.txt").await.unwrap();
+        let mut brdr = BufReader::new(file).async_byte_lines();
+        let mut count = 0;
+
+        while let Some(line) = brdr.next().await.unwrap() {
+            assert_eq!(line, format!("{}", count).as_bytes());
+            count += 1;
+        }
+
+        assert_eq!(count, 10);
+    }
+
+    #[tokio::test]
+    async fn test_reader_extensions_in_scope() {
+        use crate::{AsyncByteLinesReader, ByteLinesReader};
+
+        // slices implement both traits, so both must be callable unambiguously
+        let input = &b"one\ntwo\n"[..];
+
+        assert_eq!(input.byte_lines().next().unwrap().unwrap(), b"one");
+        assert_eq!(
+            input.async_byte_lines().next().await.unwrap(),
+            Some(&b"one"[..])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_line_source() {
+        use crate::AsyncLineSource;
+
+        let file = File::open("./res/numbers.txt").await.unwrap();
+        let brdr = crate::from_tokio(BufReader::new(file));
+        let mut source: Box<dyn AsyncLineSource> = Box::new(brdr);
+        let mut count = 0;
+
+        while let Some(line) = source.next_line().await.unwrap() {
+            assert_eq!(line, format!("{}", count).as_bytes());
+            count += 1;
+        }
+
+        assert_eq!(count, 10);
+    }
+
+    #[tokio::test]
+    async fn test_custom_delimiter() {
+        let input = &b"one\0two\r\0\0three"[..];
+        let mut brdr = crate::AsyncByteLines::with_delimiter(input, b'\0');
+        let mut lines = Vec::new();
+
+        while let Some(line) = brdr.next().await.unwrap() {
+            lines.push(line.to_vec());
+        }
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], b"one");
+        assert_eq!(lines[1], b"two\r");
+        assert_eq!(lines[2], b"");
+        assert_eq!(lines[3], b"three");
+    }
+
+    #[tokio::test]
+    async fn test_sequence_delimiter() {
+        // use a tiny buffer to force delimiters across reads
+        let input = &b"one\r\n\r\ntwo\r\nstill two\r\n\r\nthree"[..];
+        let input = BufReader::with_capacity(2, input);
+        let mut brdr = crate::AsyncByteLines::with_delimiter(input, b"\r\n\r\n");
+        let mut lines = Vec::new();
+
+        while let Some(line) = brdr.next().await.unwrap() {
+            lines.push(line.to_vec());
+        }
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], b"one");
+        assert_eq!(lines[1], b"two\r\nstill two");
+        assert_eq!(lines[2], b"three");
+    }
+
+    #[tokio::test]
+    async fn test_next_with_ending() {
+        use crate::Terminator;
+
+        let input = &b"one\rtwo\r\nthree\nfour"[..];
+        let mut brdr = crate::AsyncByteLines::with_line_ending(input, crate::LineEnding::Any);
+        let mut lines = Vec::new();
+
+        while let Some((line, terminator)) = brdr.next_with_ending().await.unwrap() {
+            lines.push((line.to_vec(), terminator));
+        }
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], (b"one".to_vec(), Terminator::Cr));
+        assert_eq!(lines[1], (b"two".to_vec(), Terminator::Crlf));
+        assert_eq!(lines[2], (b"three".to_vec(), Terminator::Lf));
+        assert_eq!(lines[3], (b"four".to_vec(), Terminator::None));
+    }
+
+    #[tokio::test]
+    async fn test_max_length() {
+        let input = &b"abc\nabcdefghij\nab"[..];
+        let input = BufReader::with_capacity(3, input);
+        let mut brdr = crate::from_tokio(input).max_length(3, crate::Overflow::Error);
+
+        assert_eq!(brdr.next().await.unwrap(), Some(&b"abc"[..]));
+        assert!(brdr.next().await.is_err());
+        assert_eq!(brdr.next().await.unwrap(), Some(&b"ab"[..]));
+        assert_eq!(brdr.next().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_strict() {
+        let input = &b"abc\nab"[..];
+        let input = BufReader::with_capacity(3, input);
+        let mut brdr = crate::from_tokio(input).strict();
+
+        assert_eq!(brdr.next().await.unwrap(), Some(&b"abc"[..]));
+        assert_eq!(
+            brdr.next().await.unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(brdr.next().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_str_lines() {
+        let input = &b"caf\xc3\xa9\nca\xffe\nok\n"[..];
+        let input = BufReader::with_capacity(3, input);
+        let mut lines = crate::from_tokio(input).into_str_lines();
+
+        assert_eq!(lines.next().await.unwrap(), Some("café"));
+        assert!(lines.next().await.is_err());
+        assert_eq!(lines.next_lossy().await.unwrap().unwrap(), "ok");
+        assert!(lines.next().await.unwrap().is_none());
+
+        let input = &b"ca\xffe\n"[..];
+        let mut lines = crate::from_tokio(input);
+
+        assert_eq!(
+            lines.next_str_lossy().await.unwrap().unwrap(),
+            "ca\u{fffd}e"
+        );
+        assert!(lines.next_str().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_next_with_position() {
+        let input = &b"one\r\n\nthree"[..];
+        let mut brdr = crate::from_tokio(BufReader::with_capacity(2, input));
+        let mut positions = Vec::new();
+
+        while let Some((_, position)) = brdr.next_with_position().await.unwrap() {
+            positions.push((position.line, position.offset, position.length));
+        }
+
+        assert_eq!(positions, vec![(1, 0, 5), (2, 5, 1), (3, 6, 5)]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_safety() {
+        use std::time::Duration;
+        use tokio::io::AsyncWriteExt;
+        use tokio::time::timeout;
+
+        let (mut client, server) = tokio::io::duplex(64);
+        let mut brdr = crate::from_tokio(BufReader::new(server));
+
+        // cancel a read after part of the line has arrived
+        client.write_all(b"par").await.unwrap();
+        let next = timeout(Duration::from_millis(10), brdr.next()).await;
+        assert!(next.is_err());
+
+        // the rest of the line should complete the partial line
+        client.write_all(b"tial\nnext\n").await.unwrap();
+        assert_eq!(brdr.next().await.unwrap(), Some(&b"partial"[..]));
+        assert_eq!(brdr.next().await.unwrap(), Some(&b"next"[..]));
+    }
+
+    #[tokio::test]
+    async fn test_into_inner() {
+        let input = &b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n\x00\x01\n\x02"[..];
+
+        for capacity in 1..=input.len() {
+            let reader = BufReader::with_capacity(capacity, input);
+            let mut lines = crate::from_tokio(reader);
+
+            assert_eq!(lines.next().await.unwrap().unwrap(), b"GET / HTTP/1.1");
+            assert_eq!(lines.next().await.unwrap().unwrap(), b"Host: localhost");
+            assert_eq!(lines.next().await.unwrap().unwrap(), b"");
+
+            let mut body = Vec::new();
+            lines.into_inner().read_to_end(&mut body).await.unwrap();
+
+            assert_eq!(body, b"\x00\x01\n\x02");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_line_endings() {
+        let input = &b"one\rtwo\r\nthree\r\rfour\n"[..];
+        let input = BufReader::with_capacity(1, input);
+        let mut brdr = crate::AsyncByteLines::with_line_ending(input, crate::LineEnding::Any);
+        let mut lines = Vec::new();
+
+        while let Some(line) = brdr.next().await.unwrap() {
+            lines.push(line.to_vec());
+        }
+
+        assert_eq!(lines, vec![&b"one"[..], b"two", b"three", b"", b"four"]);
+    }
+
+    #[tokio::test]
+    async fn test_bytes_stream() {
+        use futures_util::StreamExt;
+
+        let file = File::open("./res/numbers.txt").await.unwrap();
+        let brdr = BufReader::with_capacity(3, file);
+
+        let lines = crate::ByteLinesStream::new(brdr)
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(lines.len(), 10);
+        for i in 0..10 {
+            assert_eq!(lines[i], format!("{}", i).as_bytes());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bytes_stream_max_length() {
+        use futures_util::StreamExt;
+
+        let input = &b"one;three;two"[..];
+        let brdr = BufReader::with_capacity(2, input);
+
+        let lines = crate::ByteLinesStream::with_delimiter(brdr, b';')
+            .max_length(3, crate::Overflow::Error)
+            .map(|line| line.map_err(|e| e.kind()))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], Ok(bytes::Bytes::from_static(b"one")));
+        assert_eq!(lines[1], Err(std::io::ErrorKind::InvalidData));
+        assert_eq!(lines[2], Ok(bytes::Bytes::from_static(b"two")));
+    }
 }
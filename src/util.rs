@@ -1,28 +1,382 @@
 //! Module exposing utility handlers across read types.
+use crate::delim::{Delimiter, Terminator};
+use crate::error::Error;
+use crate::limit::Overflow;
+use crate::position::Position;
+use std::borrow::Cow;
 use std::io::Result;
+use std::mem;
 
-/// Handles a line of input and maps into the provided buffer and returns a reference.
-pub fn handle_line(input: Result<usize>, buffer: &mut Vec<u8>) -> Option<Result<&[u8]>> {
-    match input {
-        // short circuit on error
-        Err(e) => Some(Err(e)),
-
-        // no input, done
-        Ok(0) => None,
-
-        // bytes!
-        Ok(mut n) => {
-            // always "pop" the delim
-            if buffer[n - 1] == b'\n' {
-                n -= 1;
-                // also "pop" a potential leading \r
-                if n > 0 && buffer[n - 1] == b'\r' {
-                    n -= 1;
+/// Line framing state shared across read types.
+///
+/// Input is fed into the framer in chunks (typically as returned by the
+/// `fill_buf` of the underlying reader), and the framer will report back
+/// how many bytes were used and whether a line is available.
+pub struct Framer {
+    buffer: Vec<u8>,
+    delimiter: Delimiter,
+    limit: Option<(usize, Overflow)>,
+    strict: bool,
+    positioned: bool,
+    discarding: bool,
+    emitted: usize,
+    position: Position,
+    line: u64,
+    offset: u64,
+    taken: u64,
+}
+
+/// Line of input, along with the metadata collected when reading it.
+pub struct Line<'a> {
+    pub bytes: &'a [u8],
+    pub terminator: Terminator,
+    pub position: Position,
+}
+
+impl<'a> Line<'a> {
+    /// Validates the line as UTF-8, reporting the first invalid byte on error.
+    pub fn to_str(&self) -> Result<&'a str> {
+        std::str::from_utf8(self.bytes).map_err(|e| {
+            Error::InvalidUtf8 {
+                line: self.position.line,
+                offset: self.position.offset + e.valid_up_to() as u64,
+            }
+            .into()
+        })
+    }
+
+    /// Converts the line to UTF-8, replacing any invalid sequences.
+    pub fn to_str_lossy(&self) -> Cow<'a, str> {
+        String::from_utf8_lossy(self.bytes)
+    }
+}
+
+/// Status of a `Framer` after being fed input.
+pub enum Status {
+    /// A line of the given length is available, with the provided terminator.
+    Line(usize, Terminator),
+
+    /// The current line exceeded the configured maximum length.
+    Overflow,
+
+    /// The input ended without terminating the final line, in strict mode.
+    Unterminated,
+
+    /// The input has been exhausted.
+    Eof,
+}
+
+impl Framer {
+    /// Constructs a new `Framer` splitting lines on the provided delimiter.
+    pub fn new(delimiter: Delimiter) -> Self {
+        Self::with_position(
+            delimiter,
+            Position {
+                line: 1,
+                offset: 0,
+                length: 0,
+            },
+        )
+    }
+
+    /// Constructs a new `Framer` starting part way through the input.
+    ///
+    /// The line and offset of the provided position are used for the first line.
+    pub fn with_position(delimiter: Delimiter, start: Position) -> Self {
+        Self {
+            buffer: Vec::new(),
+            delimiter,
+            limit: None,
+            strict: false,
+            positioned: false,
+            discarding: false,
+            emitted: 0,
+            position: Position::default(),
+            line: start.line,
+            offset: start.offset,
+            taken: 0,
+        }
+    }
+
+    /// Sets the maximum length of a line, and how to handle lines exceeding it.
+    pub fn limit(&mut self, max: usize, overflow: Overflow) {
+        assert!(max > 0, "maximum line length must be non-zero");
+        self.limit = Some((max, overflow));
+    }
+
+    /// Requires the final line of input to be terminated, reporting it otherwise.
+    pub fn strict(&mut self) {
+        self.strict = true;
+    }
+
+    /// Wraps errors from the reader in an `Error::Io` carrying their position.
+    pub fn positioned(&mut self) {
+        self.positioned = true;
+    }
+
+    /// Retrieves the position of the most recently returned line.
+    ///
+    /// When the line exceeded the maximum length, this is the position of the
+    /// line up until the point where it overflowed.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Resumes framing, dropping the previously returned line from the buffer.
+    ///
+    /// If bytes remain from a split line and they already contain a full line,
+    /// the line is returned immediately without requiring any more input.
+    pub fn resume(&mut self) -> Option<Status> {
+        self.buffer.drain(..self.emitted);
+        self.emitted = 0;
+
+        match self.delimiter.find(&[], &self.buffer) {
+            Some(n) if n == self.buffer.len() => self.complete(),
+            _ => None,
+        }
+    }
+
+    /// Attempts to locate a line fully contained within a chunk of input.
+    ///
+    /// This allows callers to reference the line directly from the chunk rather
+    /// than copying into the buffer, which is only possible when nothing has
+    /// been buffered. The number of bytes to consume from the chunk is returned
+    /// alongside the line length and terminator.
+    pub fn borrow(&mut self, chunk: &[u8]) -> Option<(usize, usize, Terminator)> {
+        if self.buffer.len() > self.emitted || self.discarding {
+            return None;
+        }
+
+        // only the previously returned line is buffered, so it can be dropped
+        self.buffer.clear();
+        self.emitted = 0;
+
+        // bound our window to a line of maximum length
+        let window = match self.limit {
+            Some((max, _)) => &chunk[..(max + self.delimiter.max_len()).min(chunk.len())],
+            None => chunk,
+        };
+
+        // lines which are too long are left to be handled by `feed`
+        let used = self.delimiter.find(&[], window)?;
+        let (len, terminator) = self.delimiter.strip(&window[..used]);
+        match self.limit {
+            Some((max, _)) if len > max => None,
+            _ => {
+                self.taken = used as u64;
+                self.advance(used as u64, true);
+                Some((used, len, terminator))
+            }
+        }
+    }
+
+    /// Feeds a chunk of input into the framer.
+    ///
+    /// The number of bytes used from the chunk is returned, and should be
+    /// consumed from the reader. An empty chunk is treated as EOF.
+    pub fn feed(&mut self, chunk: &[u8]) -> (usize, Option<Status>) {
+        // no more input, flush what we have
+        if chunk.is_empty() {
+            return match self.complete() {
+                Some(Status::Line(_, Terminator::None)) if self.strict => {
+                    (0, Some(Status::Unterminated))
                 }
+                status => (0, Some(status.unwrap_or(Status::Eof))),
+            };
+        }
+
+        // bound our window to the room left in the buffer
+        let room = match self.limit {
+            Some((max, _)) => (max + self.delimiter.max_len()).saturating_sub(self.buffer.len()),
+            None => chunk.len(),
+        };
+        let window = &chunk[..room.min(chunk.len())];
+
+        // found the end of the line, so complete it
+        if let Some(n) = self.delimiter.find(&self.buffer, window) {
+            self.buffer.extend_from_slice(&window[..n]);
+            self.taken += n as u64;
+            return (n, self.complete());
+        }
+
+        self.buffer.extend_from_slice(window);
+        self.taken += window.len() as u64;
+
+        // no room left, so we have to overflow
+        if window.len() == room {
+            return (window.len(), self.overflow());
+        }
+
+        (window.len(), None)
+    }
+
+    /// Handles a status from the framer and maps into a line reference.
+    pub fn handle_line(&mut self, input: Result<Status>) -> Option<Result<Line<'_>>> {
+        match input {
+            // short circuit on error, noting the line being read
+            Err(e) => {
+                self.position = Position {
+                    line: self.line,
+                    offset: self.offset,
+                    length: self.taken,
+                };
+
+                // errors raised by the framer itself are already positioned
+                if !self.positioned || e.get_ref().is_some_and(|e| e.is::<Error>()) {
+                    return Some(Err(e));
+                }
+
+                Some(Err(Error::Io {
+                    source: e,
+                    line: self.line,
+                    offset: self.offset,
+                }
+                .into()))
+            }
+
+            // no input, done
+            Ok(Status::Eof) => None,
+
+            // line too long, so error
+            Ok(Status::Overflow) => Some(Err(Error::LineTooLong {
+                line: self.position.line,
+                offset: self.position.offset,
+            }
+            .into())),
+
+            // final line unterminated, so error
+            Ok(Status::Unterminated) => Some(Err(Error::UnexpectedEof {
+                line: self.position.line,
+                offset: self.position.offset,
             }
+            .into())),
+
+            // bytes! pass back the byte slice
+            Ok(Status::Line(len, terminator)) => Some(Ok(Line {
+                bytes: &self.buffer[..len],
+                terminator,
+                position: self.position,
+            })),
+        }
+    }
+
+    /// Completes the buffered line, after a delimiter or EOF has been reached.
+    fn complete(&mut self) -> Option<Status> {
+        // nothing buffered means we're at EOF
+        if self.buffer.is_empty() && !self.discarding {
+            return None;
+        }
+
+        // handle the remainder of an overflowing line
+        if self.discarding {
+            self.discarding = false;
+            return match self.limit {
+                Some((max, Overflow::Truncate)) => {
+                    // only strip the tail, as the truncated line is kept in front
+                    let (_, terminator) = self.delimiter.strip(&self.buffer[max..]);
+                    self.emit(self.buffer.len(), true);
+                    Some(Status::Line(max, terminator))
+                }
+                _ => {
+                    // error was already emitted, so just move on
+                    self.buffer.clear();
+                    self.offset += self.taken;
+                    self.taken = 0;
+                    self.line += 1;
+                    None
+                }
+            };
+        }
+
+        // strip the delimiter to find the line length
+        let (len, terminator) = self.delimiter.strip(&self.buffer);
+
+        // line fits (or has no limit)
+        let (max, overflow) = match self.limit {
+            Some((max, overflow)) if len > max => (max, overflow),
+            _ => {
+                self.emit(self.buffer.len(), true);
+                return Some(Status::Line(len, terminator));
+            }
+        };
+
+        // line was complete, but too long
+        Some(match overflow {
+            Overflow::Error => {
+                self.emit(self.buffer.len(), true);
+                Status::Overflow
+            }
+            Overflow::Truncate => {
+                self.emit(self.buffer.len(), true);
+                Status::Line(max, terminator)
+            }
+            Overflow::Split => {
+                self.emit(max, false);
+                Status::Line(max, Terminator::None)
+            }
+        })
+    }
+
+    /// Handles a full buffer which has not yet reached a delimiter.
+    fn overflow(&mut self) -> Option<Status> {
+        let (max, overflow) = self.limit?;
+
+        // splitting just emits the start of the line
+        if overflow == Overflow::Split {
+            self.emit(max, false);
+            return Some(Status::Line(max, Terminator::None));
+        }
+
+        // drop everything except enough to match a delimiter straddling chunks
+        let keep = if overflow == Overflow::Truncate {
+            max
+        } else {
+            0
+        };
+        let tail = (self.delimiter.max_len() - 1).min(self.buffer.len() - keep);
+        let start = self.buffer.len() - tail;
+        self.buffer.drain(keep..start);
+
+        // only report an error the first time
+        let discarding = mem::replace(&mut self.discarding, true);
+        match overflow {
+            Overflow::Error if !discarding => {
+                self.position = Position {
+                    line: self.line,
+                    offset: self.offset,
+                    length: self.taken,
+                };
+                Some(Status::Overflow)
+            }
+            _ => None,
+        }
+    }
+
+    /// Emits the first bytes of the buffer as a line, to be dropped on resume.
+    fn emit(&mut self, n: usize, complete: bool) {
+        // all bytes taken belong to the line, unless some are left over
+        let length = if n == self.buffer.len() {
+            self.taken
+        } else {
+            n as u64
+        };
+        self.emitted = n;
+        self.advance(length, complete);
+    }
+
+    /// Advances the position past a line of the provided length.
+    fn advance(&mut self, length: u64, complete: bool) {
+        self.position = Position {
+            line: self.line,
+            offset: self.offset,
+            length,
+        };
+        self.offset += length;
+        self.taken -= length;
 
-            // pass back the byte slice
-            Some(Ok(&buffer[..n]))
+        // fragments of a line share the line number
+        if complete {
+            self.line += 1;
         }
     }
 }